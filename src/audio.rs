@@ -0,0 +1,416 @@
+use std::{
+    collections::HashMap,
+    f32::consts::TAU,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use bevy::{
+    audio::{play_queued_audio_system, AudioOutput, Decodable},
+    reflect::TypeUuid,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// The sample rate all sounds are synthesized at
+const SAMPLE_RATE: u32 = 22050;
+/// The shortest time between two plays of the same sound effect, so painting many tiles doesn't make a wall of noise
+const SOUND_COOLDOWN_SECONDS: f64 = 0.06;
+/// How much quieter than full volume the music is synthesized, so it sits under the sound effects
+const MUSIC_LEVEL: f32 = 0.25;
+/// The name the volume settings are stored under
+const AUDIO_SETTINGS_NAME: &str = "audio";
+
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Sound>()
+            .init_non_send_resource::<AudioOutput<Sound>>()
+            .init_resource::<Audio<Sound>>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                play_queued_audio_system::<Sound>.exclusive_system(),
+            )
+            .add_event::<SoundEvent>()
+            .insert_resource(load_stored::<AudioSettings>(AUDIO_SETTINGS_NAME).unwrap_or_default())
+            .insert_resource(ChannelGains::default())
+            .insert_resource(SoundCooldowns::default())
+            .insert_resource(Sounds::synthesize())
+            .add_startup_system(start_music)
+            .add_system(audio_settings_system)
+            .add_system(sound_event_system);
+    }
+}
+
+/// Things happening in the game that have a sound effect
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SoundEvent {
+    Paint,
+    Erase,
+    DistrictValid,
+    DistrictInvalid,
+    Solved,
+    Confirm,
+}
+
+impl SoundEvent {
+    const ALL: [SoundEvent; 6] = [
+        SoundEvent::Paint,
+        SoundEvent::Erase,
+        SoundEvent::DistrictValid,
+        SoundEvent::DistrictInvalid,
+        SoundEvent::Solved,
+        SoundEvent::Confirm,
+    ];
+}
+
+/// The volume settings for one audio channel
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ChannelSettings {
+    /// From 0 to 1
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl ChannelSettings {
+    const VOLUME_STEP: f32 = 0.25;
+
+    fn gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+
+    /// Turns the volume up a step, wrapping around to muted after full volume, and then back to the lowest step
+    pub fn cycle(&mut self) {
+        if self.muted {
+            self.muted = false;
+            self.volume = ChannelSettings::VOLUME_STEP;
+        } else if self.volume >= 1.0 {
+            self.muted = true;
+        } else {
+            self.volume = (self.volume + ChannelSettings::VOLUME_STEP).min(1.0);
+        }
+    }
+
+    pub fn description(&self) -> String {
+        if self.muted {
+            "muted".to_string()
+        } else {
+            format!("{}%", (self.volume * 100.0).round())
+        }
+    }
+}
+
+/// The player's volume settings for each audio channel
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct AudioSettings {
+    pub music: ChannelSettings,
+    pub effects: ChannelSettings,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            music: ChannelSettings {
+                volume: 0.5,
+                muted: false,
+            },
+            effects: ChannelSettings {
+                volume: 0.75,
+                muted: false,
+            },
+        }
+    }
+}
+
+/// The current gain of each channel, shared with every sound playing on it so volume changes apply immediately
+#[derive(Default)]
+struct ChannelGains {
+    music: Arc<AtomicU32>,
+    effects: Arc<AtomicU32>,
+}
+
+/// A synthesized sound, played on one of the audio channels
+#[derive(TypeUuid)]
+#[uuid = "3c6a54ba-1f0e-4a39-9d38-6f1f8e2b7d45"]
+pub struct Sound {
+    samples: Arc<[f32]>,
+    /// The gain of the channel the sound plays on, stored as the bits of an `f32`
+    gain: Arc<AtomicU32>,
+    looping: bool,
+}
+
+impl Decodable for Sound {
+    type Decoder = SoundDecoder;
+    type DecoderItem = f32;
+
+    fn decoder(&self) -> Self::Decoder {
+        SoundDecoder {
+            samples: self.samples.clone(),
+            gain: self.gain.clone(),
+            looping: self.looping,
+            position: 0,
+        }
+    }
+}
+
+/// Plays back the samples of a `Sound`
+pub struct SoundDecoder {
+    samples: Arc<[f32]>,
+    gain: Arc<AtomicU32>,
+    looping: bool,
+    position: usize,
+}
+
+impl Iterator for SoundDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.position >= self.samples.len() {
+            if !self.looping || self.samples.is_empty() {
+                return None;
+            }
+            self.position = 0;
+        }
+
+        let sample =
+            self.samples[self.position] * f32::from_bits(self.gain.load(Ordering::Relaxed));
+        self.position += 1;
+        Some(sample)
+    }
+}
+
+impl rodio::Source for SoundDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
+
+/// The synthesized samples for the music and every sound effect
+struct Sounds {
+    music: Arc<[f32]>,
+    effects: HashMap<SoundEvent, Arc<[f32]>>,
+}
+
+/// When each sound effect was last played, in seconds since startup
+#[derive(Default)]
+struct SoundCooldowns(HashMap<SoundEvent, f64>);
+
+fn sine(phase: f32) -> f32 {
+    (phase * TAU).sin()
+}
+
+fn triangle(phase: f32) -> f32 {
+    4.0 * (phase - (phase + 0.5).floor()).abs() - 1.0
+}
+
+fn square(phase: f32) -> f32 {
+    if phase.fract() < 0.5 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+/// Gets the frequency of the provided MIDI note number
+fn note_frequency(note: u8) -> f32 {
+    440.0 * 2.0_f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+/// Synthesizes a note that slides between the provided frequencies, with a short fade in and a decay so it doesn't click
+fn tone(
+    waveform: fn(f32) -> f32,
+    start_frequency: f32,
+    end_frequency: f32,
+    seconds: f32,
+    level: f32,
+) -> Vec<f32> {
+    let num_samples = (seconds * SAMPLE_RATE as f32) as usize;
+    let attack_samples = (0.005 * SAMPLE_RATE as f32) as usize;
+    let mut phase = 0.0;
+    (0..num_samples)
+        .map(|i| {
+            let progress = i as f32 / num_samples as f32;
+            let frequency = start_frequency + (end_frequency - start_frequency) * progress;
+            phase += frequency / SAMPLE_RATE as f32;
+            let attack = (i as f32 / attack_samples as f32).min(1.0);
+            let decay = (1.0 - progress).powi(2);
+            waveform(phase) * attack * decay * level
+        })
+        .collect()
+}
+
+/// Mixes the provided sound into the provided buffer, starting at the provided time
+fn mix_into(buffer: &mut Vec<f32>, sound: &[f32], start_seconds: f32) {
+    let start = (start_seconds * SAMPLE_RATE as f32) as usize;
+    if buffer.len() < start + sound.len() {
+        buffer.resize(start + sound.len(), 0.0);
+    }
+    for (mixed, sample) in buffer[start..].iter_mut().zip(sound) {
+        *mixed += sample;
+    }
+}
+
+/// Synthesizes a sequence of notes, each starting the provided number of seconds after the last
+fn arpeggio(
+    waveform: fn(f32) -> f32,
+    notes: &[u8],
+    spacing: f32,
+    length: f32,
+    level: f32,
+) -> Vec<f32> {
+    let mut buffer = Vec::new();
+    for (i, note) in notes.iter().enumerate() {
+        let frequency = note_frequency(*note);
+        let sound = tone(waveform, frequency, frequency, length, level);
+        mix_into(&mut buffer, &sound, i as f32 * spacing);
+    }
+    buffer
+}
+
+/// Synthesizes a gentle loop of arpeggiated chords over a bass line
+fn synthesize_music() -> Vec<f32> {
+    const BEAT_SECONDS: f32 = 0.3;
+    // C major, A minor, F major, G major, as (bass note, chord notes)
+    let progression: [(u8, [u8; 3]); 4] = [
+        (48, [60, 64, 67]),
+        (45, [57, 60, 64]),
+        (41, [57, 60, 65]),
+        (43, [59, 62, 67]),
+    ];
+
+    let mut buffer = Vec::new();
+    for (bar, (bass, chord)) in progression.iter().enumerate() {
+        let bar_start = bar as f32 * 8.0 * BEAT_SECONDS;
+        let bass_frequency = note_frequency(*bass);
+        let bass_sound = tone(
+            sine,
+            bass_frequency,
+            bass_frequency,
+            8.0 * BEAT_SECONDS,
+            0.5,
+        );
+        mix_into(&mut buffer, &bass_sound, bar_start);
+
+        let pattern = [0, 1, 2, 1, 0, 1, 2, 1];
+        for (beat, chord_idx) in pattern.iter().enumerate() {
+            let frequency = note_frequency(chord[*chord_idx]);
+            let note = tone(triangle, frequency, frequency, BEAT_SECONDS * 1.5, 0.3);
+            mix_into(&mut buffer, &note, bar_start + beat as f32 * BEAT_SECONDS);
+        }
+    }
+
+    // end exactly on the bar line, so the loop doesn't skip a beat
+    buffer.truncate((progression.len() as f32 * 8.0 * BEAT_SECONDS * SAMPLE_RATE as f32) as usize);
+    buffer.iter().map(|sample| sample * MUSIC_LEVEL).collect()
+}
+
+impl Sounds {
+    fn synthesize() -> Self {
+        let effects = SoundEvent::ALL
+            .iter()
+            .map(|event| {
+                let samples = match event {
+                    SoundEvent::Paint => tone(sine, 880.0, 990.0, 0.04, 0.3),
+                    SoundEvent::Erase => tone(square, 330.0, 220.0, 0.05, 0.15),
+                    SoundEvent::DistrictValid => arpeggio(sine, &[76, 83], 0.07, 0.12, 0.35),
+                    SoundEvent::DistrictInvalid => arpeggio(sine, &[71, 64], 0.07, 0.12, 0.35),
+                    SoundEvent::Solved => arpeggio(triangle, &[72, 76, 79, 84], 0.09, 0.3, 0.4),
+                    SoundEvent::Confirm => {
+                        let mut chord = arpeggio(triangle, &[60, 64, 67], 0.0, 0.6, 0.25);
+                        mix_into(&mut chord, &tone(sine, 1046.5, 1046.5, 0.4, 0.2), 0.05);
+                        chord
+                    }
+                };
+                (*event, Arc::from(samples))
+            })
+            .collect();
+
+        Sounds {
+            music: Arc::from(synthesize_music()),
+            effects,
+        }
+    }
+}
+
+/// Starts the background music, which loops forever at the music channel's volume
+fn start_music(
+    sounds: Res<Sounds>,
+    gains: Res<ChannelGains>,
+    mut sound_assets: ResMut<Assets<Sound>>,
+    audio: Res<Audio<Sound>>,
+) {
+    audio.play(sound_assets.add(Sound {
+        samples: sounds.music.clone(),
+        gain: gains.music.clone(),
+        looping: true,
+    }));
+}
+
+/// Handles applying changes to the volume settings, and storing them so they carry over to the next session
+fn audio_settings_system(settings: Res<AudioSettings>, gains: Res<ChannelGains>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    // the settings are only added once, from storage, so there's no need to store them again then
+    if !settings.is_added() {
+        store(AUDIO_SETTINGS_NAME, &*settings);
+    }
+
+    gains
+        .music
+        .store(settings.music.gain().to_bits(), Ordering::Relaxed);
+    gains
+        .effects
+        .store(settings.effects.gain().to_bits(), Ordering::Relaxed);
+}
+
+/// Handles playing sound effects for things that happen in the game
+fn sound_event_system(
+    time: Res<Time>,
+    sounds: Res<Sounds>,
+    gains: Res<ChannelGains>,
+    mut cooldowns: ResMut<SoundCooldowns>,
+    mut sound_events: EventReader<SoundEvent>,
+    mut sound_assets: ResMut<Assets<Sound>>,
+    audio: Res<Audio<Sound>>,
+) {
+    let now = time.seconds_since_startup();
+    for event in sound_events.iter() {
+        let last_played = cooldowns.0.get(event).copied();
+        if last_played.map_or(false, |last_played| {
+            now - last_played < SOUND_COOLDOWN_SECONDS
+        }) {
+            continue;
+        }
+        cooldowns.0.insert(*event, now);
+
+        if let Some(samples) = sounds.effects.get(event) {
+            audio.play(sound_assets.add(Sound {
+                samples: samples.clone(),
+                gain: gains.effects.clone(),
+                looping: false,
+            }));
+        }
+    }
+}
@@ -0,0 +1,619 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::input::InputSystem;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// The name the controls are stored under
+const CONTROLS_NAME: &str = "controls";
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Controls::load())
+            .insert_resource(Actions::default())
+            .insert_resource(Rebinding(None))
+            .insert_resource(ControlsFocus(0))
+            .add_system_to_stage(CoreStage::PreUpdate, action_system.after(InputSystem))
+            .add_system(store_controls_system)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Controls).with_system(controls_screen_setup),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Controls)
+                    .with_system(rebind_button_system)
+                    .with_system(controls_input_system)
+                    .with_system(controls_focus_system)
+                    .with_system(back_button_system)
+                    .with_system(binding_text_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Controls)
+                    .with_system(despawn_components_system::<ControlsComponent>),
+            );
+    }
+}
+
+/// Things the player can do with a keyboard or gamepad
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    Paint,
+    Erase,
+    NextDistrict,
+    PreviousDistrict,
+    Confirm,
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    /// Moving the mouse while this is held pans the camera
+    DragPan,
+    NextView,
+    ExportPlan,
+    SelectDistrict1,
+    SelectDistrict2,
+    SelectDistrict3,
+    SelectDistrict4,
+    SelectDistrict5,
+    SelectDistrict6,
+    SelectDistrict7,
+    SelectDistrict8,
+    SelectDistrict9,
+    SelectDistrict10,
+}
+
+impl Action {
+    const ALL: [Action; 26] = [
+        Action::CursorUp,
+        Action::CursorDown,
+        Action::CursorLeft,
+        Action::CursorRight,
+        Action::Paint,
+        Action::Erase,
+        Action::NextDistrict,
+        Action::PreviousDistrict,
+        Action::Confirm,
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::DragPan,
+        Action::NextView,
+        Action::ExportPlan,
+        Action::SelectDistrict1,
+        Action::SelectDistrict2,
+        Action::SelectDistrict3,
+        Action::SelectDistrict4,
+        Action::SelectDistrict5,
+        Action::SelectDistrict6,
+        Action::SelectDistrict7,
+        Action::SelectDistrict8,
+        Action::SelectDistrict9,
+        Action::SelectDistrict10,
+    ];
+
+    /// The actions that select each district, in order
+    pub const SELECT_DISTRICT: [Action; 10] = [
+        Action::SelectDistrict1,
+        Action::SelectDistrict2,
+        Action::SelectDistrict3,
+        Action::SelectDistrict4,
+        Action::SelectDistrict5,
+        Action::SelectDistrict6,
+        Action::SelectDistrict7,
+        Action::SelectDistrict8,
+        Action::SelectDistrict9,
+        Action::SelectDistrict10,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Action::CursorUp => "Cursor up",
+            Action::CursorDown => "Cursor down",
+            Action::CursorLeft => "Cursor left",
+            Action::CursorRight => "Cursor right",
+            Action::Paint => "Paint",
+            Action::Erase => "Erase",
+            Action::NextDistrict => "Next district",
+            Action::PreviousDistrict => "Previous district",
+            Action::Confirm => "Confirm",
+            Action::PanUp => "Pan up",
+            Action::PanDown => "Pan down",
+            Action::PanLeft => "Pan left",
+            Action::PanRight => "Pan right",
+            Action::DragPan => "Drag to pan",
+            Action::NextView => "Next view",
+            Action::ExportPlan => "Export plan as SVG",
+            Action::SelectDistrict1 => "Select district 1",
+            Action::SelectDistrict2 => "Select district 2",
+            Action::SelectDistrict3 => "Select district 3",
+            Action::SelectDistrict4 => "Select district 4",
+            Action::SelectDistrict5 => "Select district 5",
+            Action::SelectDistrict6 => "Select district 6",
+            Action::SelectDistrict7 => "Select district 7",
+            Action::SelectDistrict8 => "Select district 8",
+            Action::SelectDistrict9 => "Select district 9",
+            Action::SelectDistrict10 => "Select district 10",
+        }
+    }
+}
+
+/// The keyboard key, gamepad button, and mouse button that trigger an action
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Binding {
+    pub key: Option<KeyCode>,
+    pub gamepad_button: Option<GamepadButtonType>,
+    /// Controls stored before mouse buttons could be bound don't have one
+    #[serde(default)]
+    pub mouse_button: Option<MouseButton>,
+}
+
+/// Which keys, gamepad buttons, and mouse buttons trigger each action
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Controls {
+    pub bindings: HashMap<Action, Binding>,
+}
+
+impl Controls {
+    /// Loads the stored controls, if there are any. Actions added since they were stored get their default bindings.
+    fn load() -> Self {
+        let mut controls = match load_stored::<Controls>(CONTROLS_NAME) {
+            Some(controls) => controls,
+            None => return Controls::default(),
+        };
+
+        for (action, mut binding) in Controls::default().bindings {
+            if controls.bindings.contains_key(&action) {
+                continue;
+            }
+
+            // a key can only do one thing at a time, so defaults that were bound to something else are left out
+            let bound = controls.bindings.values();
+            if bound.clone().any(|other| other.key == binding.key) {
+                binding.key = None;
+            }
+            if bound
+                .clone()
+                .any(|other| other.gamepad_button == binding.gamepad_button)
+            {
+                binding.gamepad_button = None;
+            }
+            if bound
+                .clone()
+                .any(|other| other.mouse_button == binding.mouse_button)
+            {
+                binding.mouse_button = None;
+            }
+            controls.bindings.insert(action, binding);
+        }
+
+        controls
+    }
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        use GamepadButtonType::*;
+        let mut bindings: HashMap<Action, Binding> = [
+            (Action::CursorUp, KeyCode::Up, Some(DPadUp)),
+            (Action::CursorDown, KeyCode::Down, Some(DPadDown)),
+            (Action::CursorLeft, KeyCode::Left, Some(DPadLeft)),
+            (Action::CursorRight, KeyCode::Right, Some(DPadRight)),
+            (Action::Paint, KeyCode::Space, Some(South)),
+            (Action::Erase, KeyCode::Back, Some(East)),
+            (Action::NextDistrict, KeyCode::RBracket, Some(RightTrigger)),
+            (
+                Action::PreviousDistrict,
+                KeyCode::LBracket,
+                Some(LeftTrigger),
+            ),
+            (Action::Confirm, KeyCode::Return, Some(Start)),
+            (Action::PanUp, KeyCode::W, None),
+            (Action::PanDown, KeyCode::S, None),
+            (Action::PanLeft, KeyCode::A, None),
+            (Action::PanRight, KeyCode::D, None),
+            (Action::NextView, KeyCode::V, Some(Select)),
+            (Action::ExportPlan, KeyCode::E, None),
+            (Action::SelectDistrict1, KeyCode::Key1, None),
+            (Action::SelectDistrict2, KeyCode::Key2, None),
+            (Action::SelectDistrict3, KeyCode::Key3, None),
+            (Action::SelectDistrict4, KeyCode::Key4, None),
+            (Action::SelectDistrict5, KeyCode::Key5, None),
+            (Action::SelectDistrict6, KeyCode::Key6, None),
+            (Action::SelectDistrict7, KeyCode::Key7, None),
+            (Action::SelectDistrict8, KeyCode::Key8, None),
+            (Action::SelectDistrict9, KeyCode::Key9, None),
+            (Action::SelectDistrict10, KeyCode::Key0, None),
+        ]
+        .into_iter()
+        .map(|(action, key, gamepad_button)| {
+            (
+                action,
+                Binding {
+                    key: Some(key),
+                    gamepad_button,
+                    mouse_button: None,
+                },
+            )
+        })
+        .collect();
+        bindings.insert(
+            Action::DragPan,
+            Binding {
+                key: None,
+                gamepad_button: None,
+                mouse_button: Some(MouseButton::Middle),
+            },
+        );
+
+        Controls { bindings }
+    }
+}
+
+/// The actions being triggered this frame
+#[derive(Default)]
+pub struct Actions {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    just_released: HashSet<Action>,
+}
+
+impl Actions {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+}
+
+/// Updates which `Actions` are being triggered based on the keyboard, gamepads, mouse, and `Controls`
+fn action_system(
+    controls: Res<Controls>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    rebinding: Res<Rebinding>,
+    mut actions: ResMut<Actions>,
+) {
+    let previously_pressed = std::mem::take(&mut actions.pressed);
+    actions.just_pressed.clear();
+    actions.just_released.clear();
+
+    // the key pressed to rebind an action shouldn't trigger anything
+    if rebinding.0.is_none() {
+        for (action, binding) in controls.bindings.iter() {
+            let key_pressed = binding.key.map_or(false, |key| keyboard.pressed(key));
+            let gamepad_button_pressed = binding.gamepad_button.map_or(false, |button_type| {
+                gamepads
+                    .iter()
+                    .any(|gamepad| gamepad_buttons.pressed(GamepadButton(*gamepad, button_type)))
+            });
+            let mouse_button_pressed = binding
+                .mouse_button
+                .map_or(false, |button| mouse_buttons.pressed(button));
+            if key_pressed || gamepad_button_pressed || mouse_button_pressed {
+                actions.pressed.insert(*action);
+            }
+        }
+    }
+
+    for action in Action::ALL {
+        match (
+            previously_pressed.contains(&action),
+            actions.pressed(action),
+        ) {
+            (false, true) => {
+                actions.just_pressed.insert(action);
+            }
+            (true, false) => {
+                actions.just_released.insert(action);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Handles storing the controls whenever they're rebound, so they carry over to the next session
+fn store_controls_system(controls: Res<Controls>) {
+    // the controls are only added once, from storage, so there's no need to store them again then
+    if controls.is_changed() && !controls.is_added() {
+        store(CONTROLS_NAME, &*controls);
+    }
+}
+
+#[derive(Component)]
+struct ControlsComponent;
+
+#[derive(Component)]
+struct RebindButton(Action);
+
+#[derive(Component)]
+struct BackButton;
+
+/// The action waiting for a new key or gamepad button, if any
+pub struct Rebinding(Option<Action>);
+
+/// Which button on the controls screen is focused for keyboard and gamepad navigation.
+/// The back button comes after all the actions.
+struct ControlsFocus(usize);
+
+/// How many actions are listed in each column of the controls screen
+const ACTIONS_PER_COLUMN: usize = 13;
+const REBIND_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(560.0),
+        height: Val::Px(32.0),
+    },
+    margin: 2.0,
+    font_size: 16.0,
+};
+const BACK_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(150.0),
+        height: Val::Px(50.0),
+    },
+    margin: 15.0,
+    font_size: 30.0,
+};
+
+/// Sets up the controls screen.
+fn controls_screen_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut focus: ResMut<ControlsFocus>,
+) {
+    focus.0 = 0;
+    let font = asset_server.load(MAIN_FONT);
+    let mono_font = asset_server.load(MONO_FONT);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::ColumnReverse,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(ControlsComponent)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    "Controls",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 50.0,
+                        color: Color::WHITE,
+                    },
+                    Default::default(),
+                ),
+                style: Style {
+                    margin: Rect {
+                        bottom: Val::Px(10.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::FlexStart,
+                        ..Default::default()
+                    },
+                    color: UiColor(Color::NONE),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for column in Action::ALL.chunks(ACTIONS_PER_COLUMN) {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::ColumnReverse,
+                                    ..Default::default()
+                                },
+                                color: UiColor(Color::NONE),
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                for action in column {
+                                    spawn_button(
+                                        parent,
+                                        &mono_font,
+                                        "",
+                                        REBIND_BUTTON,
+                                        RebindButton(*action),
+                                    );
+                                }
+                            });
+                    }
+                });
+
+            spawn_button(parent, &font, "Back", BACK_BUTTON, BackButton);
+        });
+}
+
+/// Handles starting to rebind an action when its button is clicked
+fn rebind_button_system(
+    mut rebinding: ResMut<Rebinding>,
+    interaction_query: Query<(&Interaction, &RebindButton), Changed<Interaction>>,
+) {
+    for (interaction, rebind_button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            rebinding.0 = Some(rebind_button.0);
+        }
+    }
+}
+
+/// Handles binding keys, gamepad buttons, and mouse buttons, and moving between and pressing buttons on the controls screen without a mouse
+#[allow(clippy::too_many_arguments)]
+fn controls_input_system(
+    actions: Res<Actions>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut controls: ResMut<Controls>,
+    mut rebinding: ResMut<Rebinding>,
+    mut focus: ResMut<ControlsFocus>,
+    mut game_state: ResMut<State<GameState>>,
+) {
+    if let Some(action) = rebinding.0 {
+        if keyboard.just_pressed(KeyCode::Escape) {
+            rebinding.0 = None;
+        } else if let Some(key) = keyboard.get_just_pressed().next() {
+            // a key can only do one thing at a time
+            for binding in controls.bindings.values_mut() {
+                if binding.key == Some(*key) {
+                    binding.key = None;
+                }
+            }
+            if let Some(binding) = controls.bindings.get_mut(&action) {
+                binding.key = Some(*key);
+            }
+            rebinding.0 = None;
+        } else if let Some(GamepadButton(_, button_type)) =
+            gamepad_buttons.get_just_pressed().next()
+        {
+            for binding in controls.bindings.values_mut() {
+                if binding.gamepad_button == Some(*button_type) {
+                    binding.gamepad_button = None;
+                }
+            }
+            if let Some(binding) = controls.bindings.get_mut(&action) {
+                binding.gamepad_button = Some(*button_type);
+            }
+            rebinding.0 = None;
+        } else if let Some(button) = mouse_buttons
+            .get_just_pressed()
+            // the left button clicks the rebind buttons, and paints, so it can't be bound
+            .find(|button| **button != MouseButton::Left)
+        {
+            for binding in controls.bindings.values_mut() {
+                if binding.mouse_button == Some(*button) {
+                    binding.mouse_button = None;
+                }
+            }
+            if let Some(binding) = controls.bindings.get_mut(&action) {
+                binding.mouse_button = Some(*button);
+            }
+            rebinding.0 = None;
+        }
+        return;
+    }
+
+    // the back button comes after all the actions
+    let num_buttons = Action::ALL.len() + 1;
+    if actions.just_pressed(Action::CursorUp) {
+        focus.0 = (focus.0 + num_buttons - 1) % num_buttons;
+    } else if actions.just_pressed(Action::CursorDown) {
+        focus.0 = (focus.0 + 1) % num_buttons;
+    } else if actions.just_pressed(Action::CursorLeft) && focus.0 >= ACTIONS_PER_COLUMN {
+        // the first column of actions is on the left
+        focus.0 = (focus.0 - ACTIONS_PER_COLUMN).min(Action::ALL.len() - 1);
+    } else if actions.just_pressed(Action::CursorRight) && focus.0 < ACTIONS_PER_COLUMN {
+        focus.0 = (focus.0 + ACTIONS_PER_COLUMN).min(Action::ALL.len() - 1);
+    } else if actions.just_pressed(Action::Confirm) {
+        match Action::ALL.get(focus.0) {
+            Some(action) => rebinding.0 = Some(*action),
+            None => game_state.set(GameState::Menu).unwrap(),
+        }
+    } else if keyboard.just_pressed(KeyCode::Escape) {
+        game_state.set(GameState::Menu).unwrap();
+    }
+}
+
+/// Handles highlighting the button focused for keyboard and gamepad navigation
+fn controls_focus_system(
+    focus: Res<ControlsFocus>,
+    mut rebind_button_query: Query<(&RebindButton, &mut UiColor), Without<BackButton>>,
+    mut back_button_query: Query<&mut UiColor, With<BackButton>>,
+) {
+    if !focus.is_changed() {
+        return;
+    }
+
+    let focused_action = Action::ALL.get(focus.0);
+    for (rebind_button, mut color) in rebind_button_query.iter_mut() {
+        *color = if focused_action == Some(&rebind_button.0) {
+            HOVERED_BUTTON.into()
+        } else {
+            NORMAL_BUTTON.into()
+        };
+    }
+    for mut color in back_button_query.iter_mut() {
+        *color = if focused_action.is_none() {
+            HOVERED_BUTTON.into()
+        } else {
+            NORMAL_BUTTON.into()
+        };
+    }
+}
+
+type InteractedBackButtonTuple = (Changed<Interaction>, With<BackButton>);
+
+/// Handles interactions with the back button.
+fn back_button_system(
+    mut game_state: ResMut<State<GameState>>,
+    interaction_query: Query<&Interaction, InteractedBackButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            game_state.set(GameState::Menu).unwrap();
+        }
+    }
+}
+
+/// Handles displaying what each action is bound to
+fn binding_text_system(
+    controls: Res<Controls>,
+    rebinding: Res<Rebinding>,
+    button_query: Query<(&RebindButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (rebind_button, children) in button_query.iter() {
+        let action = rebind_button.0;
+        let mut text = match text_query.get_mut(children[0]) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+        text.sections[0].value = if rebinding.0 == Some(action) {
+            format!(
+                "{}: press a key or button (Escape to cancel)",
+                action.name()
+            )
+        } else {
+            let binding = &controls.bindings[&action];
+            format!(
+                "{:<18} {:<10} {:<13} {}",
+                action.name(),
+                binding
+                    .key
+                    .map_or("-".to_string(), |key| format!("{:?}", key)),
+                binding
+                    .gamepad_button
+                    .map_or("-".to_string(), |button| format!("{:?}", button)),
+                binding
+                    .mouse_button
+                    .map_or("-".to_string(), |button| format!("{:?}", button)),
+            )
+        };
+    }
+}
@@ -1,3 +1,5 @@
+use bevy::input::InputSystem;
+
 use crate::*;
 
 pub struct CursorPositionPlugin;
@@ -5,7 +7,10 @@ pub struct CursorPositionPlugin;
 impl Plugin for CursorPositionPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(CursorPosition(None))
-            .add_system_to_stage(CoreStage::PreUpdate, cursor_position_system);
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                cursor_position_system.after(InputSystem),
+            );
     }
 }
 
@@ -14,10 +19,11 @@ pub struct CursorPosition(pub Option<Vec2>);
 #[derive(Component)]
 pub struct MainCamera;
 
-/// Updates the game's `CursorPosition`
+/// Updates the game's `CursorPosition`, using the mouse cursor or else the current touch
 /// From https://bevy-cheatbook.github.io/cookbook/cursor2world.html
 fn cursor_position_system(
     windows: Res<Windows>,
+    touches: Res<Touches>,
     mut cursor_position: ResMut<CursorPosition>,
     query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
 ) {
@@ -28,10 +34,27 @@ fn cursor_position_system(
     // get the window that the camera is displaying to
     let window = windows.get(camera.window).unwrap();
 
-    // check if the cursor is inside the window and get its position
-    if let Some(screen_pos) = window.cursor_position() {
+    // touches that were just lifted still count, so a tap can be handled on the frame it ends
+    let touch_pos = touches
+        .iter()
+        .chain(touches.iter_just_released())
+        .next()
+        .map(|touch| {
+            let pos = touch.position();
+            // touch positions are measured from the top of the window everywhere except mobile,
+            // but cursor positions are always measured from the bottom
+            if cfg!(target_os = "android") || cfg!(target_os = "ios") {
+                pos
+            } else {
+                Vec2::new(pos.x, window.height() - pos.y)
+            }
+        });
+
+    // check if the cursor is inside the window and get its position.
+    // touches win over the mouse, since browsers also move the mouse cursor to wherever the screen was touched
+    if let Some(screen_pos) = touch_pos.or_else(|| window.cursor_position()) {
         // get the size of the window
-        let window_size = Vec2::new(window.width() as f32, window.height() as f32);
+        let window_size = Vec2::new(window.width(), window.height());
 
         // convert screen position [0..resolution] to ndc [-1..1] (gpu coordinates)
         let ndc = (screen_pos / window_size) * 2.0 - Vec2::ONE;
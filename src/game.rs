@@ -1,11 +1,86 @@
-use std::{cmp::Ordering, collections::HashSet};
+use std::{
+    cmp::Ordering,
+    collections::{HashSet, VecDeque},
+};
 
 use crate::*;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+mod history;
+use history::*;
+
+mod tools;
+use tools::*;
+
+mod keyboard_cursor;
+use keyboard_cursor::*;
+
+mod touch;
+use touch::*;
+
+mod camera;
+use camera::*;
+
+mod tooltip;
+use tooltip::*;
+
+mod balance;
+use balance::*;
+
+mod merge_split;
+use merge_split::*;
+
+mod map_render;
+use map_render::*;
+
+mod view;
+use view::*;
+
+mod results;
+use results::*;
+
+mod selector_scroll;
+use selector_scroll::*;
+
+mod sound_events;
+use sound_events::*;
+
+mod svg_export;
+use svg_export::*;
+
+mod election_timer;
+use election_timer::*;
+
+mod pause;
+use pause::*;
+
+mod save;
+pub use save::saved_run_exists;
+use save::*;
 
 const EMPTY_TILE_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
 const EMPTY_TILE_COLOR_FADED: Color = Color::rgb(0.8, 0.8, 0.8);
-const BORDER_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.0);
+const BORDER_COLOR: Color = Color::BLACK;
+const MIN_TURNOUT: u8 = 40;
+/// The style of the small buttons in the column next to the map
+const SMALL_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(60.0),
+        height: Val::Px(40.0),
+    },
+    margin: 5.0,
+    font_size: 20.0,
+};
+/// The style of the district selectors and the confirm button
+const WIDE_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(100.0),
+        height: Val::Px(50.0),
+    },
+    margin: 5.0,
+    font_size: 20.0,
+};
 const STARTING_LEVEL: Level = Level {
     districts: 3,
     good_pct: 0.5,
@@ -13,6 +88,12 @@ const STARTING_LEVEL: Level = Level {
     map_size: 10,
     min_district_size: 28,
     max_district_size: 32,
+    tolerance: PopulationTolerance::Percent(0.05),
+    empty_tile_rule: EmptyTileRule::AssignAll,
+    tie_rule: TieRule::Lose,
+    good_is_incumbent: false,
+    time_limit: time_limit_for_map_size(10),
+    seed: 0,
 };
 
 pub struct GamePlugin;
@@ -24,28 +105,152 @@ impl Plugin for GamePlugin {
                 SystemSet::on_exit(GameState::Game)
                     .with_system(despawn_components_system::<GameComponent>),
             )
-            .add_system(district_selection_system)
-            .add_system(tile_click_system)
-            .add_system(map_update_system)
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(district_selection_system)
+                    .with_system(paint_input_system.label(PaintInputSystem))
+                    .with_system(
+                        tile_click_system
+                            .label(MapEditSystem)
+                            .after(PaintInputSystem),
+                    )
+                    .with_system(tooltip_system.after(DistrictResultsSystem))
+                    .with_system(balance_button_system)
+                    .with_system(auto_balance_system.label(MapEditSystem))
+                    .with_system(erase_mode_system)
+                    .with_system(undo_redo_system.label(MapEditSystem))
+                    .with_system(tool_selection_system)
+                    .with_system(brush_size_system)
+                    .with_system(keyboard_cursor_system.label(MapEditSystem))
+                    .with_system(camera_zoom_system)
+                    .with_system(camera_pan_system)
+                    .with_system(selector_scroll_system)
+                    .with_system(export_button_system.after(DistrictResultsSystem))
+                    .with_system(
+                        election_timer_system
+                            .label(ElectionTimerSystem)
+                            .label(MapEditSystem),
+                    )
+                    .with_system(
+                        confirm_button_system
+                            .label(MapEditSystem)
+                            .after(ElectionTimerSystem),
+                    )
+                    .with_system(autosave_system),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::Results)
+                    .with_system(results_setup.after(DistrictResultsSystem))
+                    .with_system(confirm_sound_event_system)
+                    .with_system(save_election_outcome_system.after(DistrictResultsSystem)),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Results)
+                    .with_system(results_reveal_system)
+                    .with_system(continue_button_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Results)
+                    .with_system(despawn_components_system::<ResultsComponent>)
+                    .with_system(record_election_system.label(RecordElectionSystem))
+                    .with_system(next_level_system.after(RecordElectionSystem)),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::Paused)
+                    .with_system(pause_setup)
+                    .with_system(save_run_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Paused)
+                    .with_system(pause_button_system.label(MapEditSystem)),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Paused)
+                    .with_system(despawn_components_system::<PauseComponent>),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::Menu).with_system(reset_run_system))
+            .add_system_set(
+                SystemSet::on_enter(GameState::GameOver).with_system(delete_saved_run_system),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::GameOver).with_system(reset_run_system))
+            .add_system(pause_toggle_system)
+            .add_system(fit_map_system)
+            .add_system(party_text_system)
+            .add_system(split_line_preview_system)
+            .add_system(free_district_text_system)
+            .add_system(rectangle_preview_system)
+            .add_system(keyboard_cursor_display_system)
+            .add_system(map_graphics_setup_system)
+            .add_system(view_selection_system)
+            .add_system(margin_overlay_system)
+            .add_system(map_update_system.after(DistrictResultsSystem))
             .add_system(border_system)
-            .add_system(district_info_system)
-            .add_system(solution_system)
+            .add_system(tile_label_system.after(DistrictResultsSystem))
+            .add_system(district_info_system.after(DistrictResultsSystem))
+            .add_system(summary_system.after(DistrictResultsSystem))
+            .add_system(solution_system.after(DistrictResultsSystem))
+            .add_system(
+                district_results_system
+                    .label(DistrictResultsSystem)
+                    .after(MapEditSystem),
+            )
+            .add_system(map_sound_event_system.after(DistrictResultsSystem))
             .add_system(confirm_button_visibility_system)
-            .add_system(confirm_button_system)
             .insert_resource(SelectedDistrict(0))
             .insert_resource(Solved(false))
+            .insert_resource(DistrictResults(Vec::new()))
+            .insert_resource(PlanComplete(false))
             .insert_resource(Score(0))
+            .insert_resource(RunStats::default())
+            .insert_resource(ResumeRun(false))
+            .insert_resource(Autosave::default())
+            .insert_resource(ElectionTimer {
+                remaining: STARTING_LEVEL.time_limit,
+            })
+            .insert_resource(History::default())
+            .insert_resource(SelectedTool(Tool::Brush))
+            .insert_resource(SelectedView(ViewMode::Voters))
+            .insert_resource(MarginOverlay(false))
+            .insert_resource(SelectorScroll::default())
+            .insert_resource(SoundSnapshot::default())
+            .insert_resource(BrushSize(1))
+            .insert_resource(RectangleDrag::default())
+            .insert_resource(SplitLine::default())
+            .insert_resource(Stroke::default())
+            .insert_resource(KeyboardCursor::default())
+            .insert_resource(EraseMode(false))
+            .insert_resource(PaintInput::default())
+            .insert_resource(TouchGesture::default())
+            .insert_resource(AllowOutcomeChanges(false))
+            .insert_resource(AutoBalance::default())
             .insert_resource(STARTING_LEVEL)
             .insert_resource(Map {
                 tiles: vec![],
                 num_non_empty_tiles: 0,
-            });
+            })
+            .insert_resource(MapLayout::new(0, 0));
     }
 }
 
 #[derive(Component)]
 struct GameComponent;
 
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+struct PaintInputSystem;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+struct RecordElectionSystem;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+struct ElectionTimerSystem;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+struct DistrictResultsSystem;
+
+/// Label for the systems that change the map, so the cached district results can be updated after them
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+struct MapEditSystem;
+
 #[derive(Component)]
 struct DistrictSelector(u8);
 
@@ -55,20 +260,54 @@ struct ConfirmButton;
 #[derive(Component)]
 struct ConfirmButtonParent;
 
+/// The text telling the player which party they're in
 #[derive(Component)]
-enum Border {
-    Top,
-    Bottom,
-    Left,
-    Right,
-}
+struct PartyText;
+
+#[derive(Component)]
+struct SummaryText;
 
 struct SelectedDistrict(u8);
 
 struct Solved(bool);
 
-struct Score(u32);
+/// The results for all the districts, recalculated only when the map or level changes, so the systems showing them don't each recalculate them
+struct DistrictResults(Vec<DistrictResult>);
+
+/// Whether every district is valid and every tile that needs a district has one, so an election can be held on the plan
+struct PlanComplete(bool);
+
+pub struct Score(pub u32);
+
+/// Whether starting the game should pick up the saved run instead of starting a new one
+pub struct ResumeRun(pub bool);
+
+/// How the current run has gone so far, shown when it's over
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub elections_won: u32,
+    /// The total number of seats the player's party has won across all elections
+    pub seats_won: usize,
+    /// The total number of seats the other party has won across all elections
+    pub seats_lost: usize,
+    /// The width and height of the biggest map an election was held on
+    pub largest_map_size: usize,
+}
+
+impl RunStats {
+    /// Adds the results of an election to the stats
+    fn record_election(&mut self, results: &[DistrictResult], level: &Level) {
+        let (good_seats, bad_seats) = count_seats(results, level);
+        if majority_of_seats(results, level) {
+            self.elections_won += 1;
+        }
+        self.seats_won += good_seats;
+        self.seats_lost += bad_seats;
+        self.largest_map_size = self.largest_map_size.max(level.map_size);
+    }
+}
 
+#[derive(Clone, Serialize, Deserialize)]
 struct Map {
     tiles: Vec<Vec<MapTile>>,
     num_non_empty_tiles: usize,
@@ -76,16 +315,18 @@ struct Map {
 
 impl Map {
     fn generate(level: &Level) -> Self {
+        let mut rng = StdRng::seed_from_u64(level.seed);
         let mut num_non_empty_tiles = 0;
         let mut rows = Vec::new();
         for y in 0..level.map_size {
             let mut row = Vec::new();
             for x in 0..level.map_size {
-                let tile = if rand::thread_rng().gen::<f32>() <= level.populated_pct {
+                let tile = if rng.gen::<f32>() <= level.populated_pct {
                     num_non_empty_tiles += 1;
-                    match rand::thread_rng().gen::<f32>() {
-                        r if r <= level.good_pct => MapTile::new_good(x, y),
-                        _ => MapTile::new_bad(x, y),
+                    let turnout = rng.gen_range(MIN_TURNOUT..=100);
+                    match rng.gen::<f32>() {
+                        r if r <= level.good_pct => MapTile::new_good(x, y, turnout),
+                        _ => MapTile::new_bad(x, y, turnout),
                     }
                 } else {
                     MapTile::new_empty(x, y)
@@ -160,9 +401,19 @@ impl Map {
     }
 
     /// Calculates results for all the districts
-    fn get_district_results(&self, num_districts: u8) -> Vec<DistrictResult> {
+    fn get_district_results(&self, level: &Level) -> Vec<DistrictResult> {
+        if level.empty_tile_rule == EmptyTileRule::AutoAssign {
+            // judge the plan the way it will look once it's confirmed
+            self.with_empty_tiles_filled().tally_districts(level)
+        } else {
+            self.tally_districts(level)
+        }
+    }
+
+    /// Calculates results for all the districts, using the tiles' current district assignments
+    fn tally_districts(&self, level: &Level) -> Vec<DistrictResult> {
         let mut results = Vec::new();
-        for district_id in 0..num_districts {
+        for district_id in 0..level.districts {
             let tiles = self.get_tiles_in_district(district_id);
             let good_tiles = tiles
                 .iter()
@@ -181,16 +432,75 @@ impl Map {
             } else {
                 None
             };
+            let tie_winner = if winner == Some(DistrictWinner::Tie) {
+                Some(level.tie_rule.decide(level, district_id, &tiles))
+            } else {
+                None
+            };
 
             results.push(DistrictResult {
                 size: good_tiles + bad_tiles,
+                good_votes: good_tiles,
+                bad_votes: bad_tiles,
                 winner,
+                tie_winner,
             });
         }
 
         results
     }
 
+    /// Determines whether every tile that needs to be in a district is in one
+    fn all_tiles_assigned(&self, level: &Level) -> bool {
+        self.tiles.iter().all(|row| {
+            row.iter().all(|tile| {
+                tile.district_id.is_some()
+                    || (tile.content == MapTileContent::Empty
+                        && level.empty_tile_rule != EmptyTileRule::AssignAll)
+            })
+        })
+    }
+
+    /// Creates a copy of this map with its unassigned empty tiles added to neighboring districts
+    fn with_empty_tiles_filled(&self) -> Map {
+        let mut map = self.clone();
+        map.fill_empty_tiles();
+        map
+    }
+
+    /// Adds unassigned empty tiles to neighboring districts.
+    /// Districts grow outward one tile at a time, so a filled tile is always connected to the district it joined.
+    fn fill_empty_tiles(&mut self) {
+        let mut to_visit = VecDeque::new();
+        for row in self.tiles.iter() {
+            for tile in row {
+                if tile.district_id.is_some() {
+                    to_visit.push_back(tile.coords.clone());
+                }
+            }
+        }
+
+        while let Some(coords) = to_visit.pop_front() {
+            let district_id = self.get(&coords).district_id;
+            let neighbors = [
+                self.get_up(&coords),
+                self.get_down(&coords),
+                self.get_left(&coords),
+                self.get_right(&coords),
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|tile| tile.content == MapTileContent::Empty && tile.district_id.is_none())
+            .map(|tile| tile.coords.clone())
+            .collect::<Vec<Coordinates>>();
+
+            for neighbor in neighbors {
+                self.get_mut(&neighbor).district_id = district_id;
+                to_visit.push_back(neighbor);
+            }
+        }
+    }
+
     /// Gets all the tiles in the provided district
     fn get_tiles_in_district(&self, district_id: u8) -> Vec<&MapTile> {
         let mut tiles = Vec::new();
@@ -205,6 +515,29 @@ impl Map {
         tiles
     }
 
+    /// Gets the coordinates of the tile in the provided district closest to the district's center, where its label goes
+    fn district_label_coords(&self, district_id: u8) -> Option<Coordinates> {
+        let tiles = self.get_tiles_in_district(district_id);
+        let positions = tiles
+            .iter()
+            .map(|tile| Vec2::new(tile.coords.x as f32, tile.coords.y as f32))
+            .collect::<Vec<Vec2>>();
+        if positions.is_empty() {
+            return None;
+        }
+
+        let center = positions.iter().sum::<Vec2>() / positions.len() as f32;
+        tiles
+            .iter()
+            .zip(positions)
+            .min_by(|(_, a), (_, b)| {
+                a.distance_squared(center)
+                    .partial_cmp(&b.distance_squared(center))
+                    .unwrap_or(Ordering::Equal)
+            })
+            .map(|(tile, _)| tile.coords.clone())
+    }
+
     /// Gets all the tiles with the provided content
     fn get_tiles_with_content(&self, content: MapTileContent) -> Vec<&MapTile> {
         let mut tiles = Vec::new();
@@ -231,12 +564,39 @@ fn are_contiguous(tiles: &[&MapTile]) -> bool {
     }
 }
 
+#[derive(Clone)]
 struct DistrictResult {
     size: usize,
+    /// The number of voters in the district who will vote for the good party
+    good_votes: usize,
+    /// The number of voters in the district who will vote for the bad party
+    bad_votes: usize,
     winner: Option<DistrictWinner>,
+    /// If the district is tied, the party the level's tie rule awarded it to
+    tie_winner: Option<DistrictWinner>,
 }
 
 impl DistrictResult {
+    /// Determines which party gets the seat for this district, after any tie has been broken
+    fn seat_winner(&self) -> Option<DistrictWinner> {
+        match self.winner {
+            Some(DistrictWinner::Tie) => self.tie_winner,
+            winner => winner,
+        }
+    }
+
+    /// Gets which party holds the district's seat once the election is held.
+    /// Districts that break the level's rules, like ones left unfinished when time ran out, go to the bad party.
+    fn elected_party(&self, level: &Level) -> DistrictWinner {
+        if self.validity(level) == DistrictValidity::Valid
+            && self.seat_winner() == Some(DistrictWinner::Good)
+        {
+            DistrictWinner::Good
+        } else {
+            DistrictWinner::Bad
+        }
+    }
+
     fn validity(&self, level: &Level) -> DistrictValidity {
         if self.size < level.min_district_size {
             DistrictValidity::TooSmall
@@ -258,13 +618,86 @@ enum DistrictValidity {
     Valid,
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum DistrictWinner {
     Good,
     Bad,
     Tie,
 }
 
+/// How a district with an equal number of voters for each party gets decided
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum TieRule {
+    /// Tied districts go to the other party
+    Lose,
+    /// A coin flip decided by the level seed
+    CoinFlip,
+    /// The party currently in power keeps the district
+    Incumbent,
+    /// Whichever party's voters turn out in larger numbers wins
+    Turnout,
+}
+
+impl TieRule {
+    /// Picks a tie rule at random
+    fn random(rng: &mut impl Rng) -> Self {
+        match rng.gen_range(0..4) {
+            0 => TieRule::Lose,
+            1 => TieRule::CoinFlip,
+            2 => TieRule::Incumbent,
+            _ => TieRule::Turnout,
+        }
+    }
+
+    /// Gets a short description of how this rule decides ties
+    fn description(&self) -> &'static str {
+        match self {
+            TieRule::Lose => "ties lose",
+            TieRule::CoinFlip => "coin flip",
+            TieRule::Incumbent => "incumbent wins",
+            TieRule::Turnout => "larger turnout wins",
+        }
+    }
+
+    /// Decides which party wins a tied district made up of the provided tiles
+    fn decide(&self, level: &Level, district_id: u8, tiles: &[&MapTile]) -> DistrictWinner {
+        match self {
+            TieRule::Lose => DistrictWinner::Bad,
+            TieRule::CoinFlip => {
+                let mut rng = StdRng::seed_from_u64(level.seed.wrapping_add(district_id as u64));
+                if rng.gen::<bool>() {
+                    DistrictWinner::Good
+                } else {
+                    DistrictWinner::Bad
+                }
+            }
+            TieRule::Incumbent => {
+                if level.good_is_incumbent {
+                    DistrictWinner::Good
+                } else {
+                    DistrictWinner::Bad
+                }
+            }
+            TieRule::Turnout => {
+                let turnout_for = |content| {
+                    tiles
+                        .iter()
+                        .filter(|tile| tile.content == content)
+                        .map(|tile| tile.turnout as u32)
+                        .sum::<u32>()
+                };
+                // the other party wins if turnout is tied too
+                if turnout_for(MapTileContent::Good) > turnout_for(MapTileContent::Bad) {
+                    DistrictWinner::Good
+                } else {
+                    DistrictWinner::Bad
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct Level {
     /// The number of districts required
     districts: u8,
@@ -278,21 +711,141 @@ struct Level {
     min_district_size: usize,
     /// The maximum population in a district
     max_district_size: usize,
+    /// How far district populations may stray from the ideal size
+    tolerance: PopulationTolerance,
+    /// Whether tiles without any voters have to be put in a district
+    empty_tile_rule: EmptyTileRule,
+    /// How tied districts are decided
+    tie_rule: TieRule,
+    /// Whether the good party is currently in power
+    good_is_incumbent: bool,
+    /// How many seconds the player has to draw the districts before the election is held
+    time_limit: f32,
+    /// The seed used to generate the map and flip coins for tied districts
+    seed: u64,
 }
 
 impl Level {
     /// Sets min and max district sizes based on the provided number of non-empty tiles on the map
     fn set_district_sizes(&mut self, num_non_empty_tiles: usize) {
-        let avg_district_size = num_non_empty_tiles as f32 / self.districts as f32;
-        self.min_district_size = (avg_district_size * 0.95).round() as usize;
-        self.max_district_size = (avg_district_size * 1.05).round() as usize;
+        let (min_district_size, max_district_size) = self
+            .tolerance
+            .district_size_range(self.ideal_district_size(num_non_empty_tiles));
+        self.min_district_size = min_district_size;
+        self.max_district_size = max_district_size;
+    }
+
+    /// Determines the district size that would split the provided number of non-empty tiles evenly
+    fn ideal_district_size(&self, num_non_empty_tiles: usize) -> f32 {
+        num_non_empty_tiles as f32 / self.districts as f32
+    }
+}
+
+/// What happens to tiles without any voters in them
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum EmptyTileRule {
+    /// Empty tiles have to be put in a district like any other tile
+    AssignAll,
+    /// Empty tiles may be left out of every district
+    LeaveUnassigned,
+    /// Empty tiles left out of every district join a neighboring district when the plan is confirmed
+    AutoAssign,
+}
+
+impl EmptyTileRule {
+    /// Picks an empty tile rule at random
+    fn random(rng: &mut impl Rng) -> Self {
+        match rng.gen_range(0..3) {
+            0 => EmptyTileRule::AssignAll,
+            1 => EmptyTileRule::LeaveUnassigned,
+            _ => EmptyTileRule::AutoAssign,
+        }
     }
+
+    /// Gets an explanation of this rule
+    fn description(&self) -> &'static str {
+        match self {
+            EmptyTileRule::AssignAll => "Every tile must be in a district.",
+            EmptyTileRule::LeaveUnassigned => "Empty tiles can be left out of districts.",
+            EmptyTileRule::AutoAssign => {
+                "Empty tiles left out of districts will join a neighboring district."
+            }
+        }
+    }
+}
+
+/// How far a district's population is allowed to be from the ideal district size
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum PopulationTolerance {
+    /// Districts may be up to this fraction bigger or smaller than the ideal size, rounded to the nearest voter
+    Percent(f32),
+    /// Like `Percent`, but the limits are rounded toward the ideal size
+    StrictPercent(f32),
+    /// "One person, one vote": districts may be at most this many voters bigger or smaller than the ideal size
+    Absolute(usize),
 }
 
-#[derive(Hash, PartialEq, Eq)]
+impl PopulationTolerance {
+    /// Picks a tolerance for a level with the provided map size, getting stricter as maps get bigger
+    fn for_map_size(map_size: usize) -> Self {
+        match map_size {
+            x if x < 14 => PopulationTolerance::Percent(0.05),
+            x if x < 18 => PopulationTolerance::StrictPercent(0.05),
+            _ => PopulationTolerance::Absolute(2),
+        }
+    }
+
+    /// Determines the min and max district sizes allowed around the provided ideal size
+    fn district_size_range(&self, ideal_size: f32) -> (usize, usize) {
+        let (min, max) = match *self {
+            PopulationTolerance::Percent(pct) => (
+                (ideal_size * (1.0 - pct)).round(),
+                (ideal_size * (1.0 + pct)).round(),
+            ),
+            PopulationTolerance::StrictPercent(pct) => (
+                (ideal_size * (1.0 - pct)).ceil(),
+                (ideal_size * (1.0 + pct)).floor(),
+            ),
+            PopulationTolerance::Absolute(voters) => (
+                (ideal_size - voters as f32).ceil(),
+                (ideal_size + voters as f32).floor(),
+            ),
+        };
+
+        // never make the range so tight that the voters can't be split up at all
+        (
+            min.min(ideal_size.floor()).max(0.0) as usize,
+            max.max(ideal_size.ceil()) as usize,
+        )
+    }
+
+    /// Gets an explanation of this tolerance, for a level with the provided ideal district size
+    fn description(&self, ideal_size: f32) -> String {
+        match *self {
+            PopulationTolerance::Percent(pct) => format!(
+                "Districts may be up to {}% bigger or smaller than the ideal size of {:.1} voters.",
+                (pct * 100.0).round(),
+                ideal_size,
+            ),
+            PopulationTolerance::StrictPercent(pct) => format!(
+                "Districts may be up to {}% bigger or smaller than the ideal size of {:.1} voters,\nrounded toward the ideal size.",
+                (pct * 100.0).round(),
+                ideal_size,
+            ),
+            PopulationTolerance::Absolute(voters) => format!(
+                "One person, one vote: districts may be at most {} voters away from the ideal size of {:.1}.",
+                voters, ideal_size,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 struct MapTile {
     coords: Coordinates,
     content: MapTileContent,
+    /// The percentage of this tile's voters that will turn out to vote
+    turnout: u8,
     district_id: Option<u8>,
 }
 
@@ -331,7 +884,7 @@ impl MapTile {
     }
 }
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 enum MapTileContent {
     Good,
     Bad,
@@ -339,24 +892,25 @@ enum MapTileContent {
 }
 
 impl MapTile {
-    fn with_content(coords: Coordinates, content: MapTileContent) -> Self {
+    fn with_content(coords: Coordinates, content: MapTileContent, turnout: u8) -> Self {
         MapTile {
             coords,
             content,
+            turnout,
             district_id: None,
         }
     }
 
-    fn new_good(x: usize, y: usize) -> Self {
-        MapTile::with_content(Coordinates { x, y }, MapTileContent::Good)
+    fn new_good(x: usize, y: usize, turnout: u8) -> Self {
+        MapTile::with_content(Coordinates { x, y }, MapTileContent::Good, turnout)
     }
 
-    fn new_bad(x: usize, y: usize) -> Self {
-        MapTile::with_content(Coordinates { x, y }, MapTileContent::Bad)
+    fn new_bad(x: usize, y: usize, turnout: u8) -> Self {
+        MapTile::with_content(Coordinates { x, y }, MapTileContent::Bad, turnout)
     }
 
     fn new_empty(x: usize, y: usize) -> Self {
-        MapTile::with_content(Coordinates { x, y }, MapTileContent::Empty)
+        MapTile::with_content(Coordinates { x, y }, MapTileContent::Empty, 0)
     }
 
     fn color(&self, colors: &Colors) -> Color {
@@ -368,198 +922,174 @@ impl MapTile {
     }
 }
 
-#[derive(Component, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 struct Coordinates {
     x: usize,
     y: usize,
 }
 
+/// Where the map's tiles are displayed in the world
+struct MapLayout {
+    tile_size: Vec3,
+    /// The space between neighboring tiles
+    tile_spacing: f32,
+    /// The position of the center of the bottom left tile
+    origin: Vec3,
+    num_rows: usize,
+    num_columns: usize,
+}
+
+impl MapLayout {
+    /// Creates a layout for a map with the provided dimensions, centered on the origin
+    fn new(num_rows: usize, num_columns: usize) -> Self {
+        let tile_spacing = 1.0;
+        let tile_size = Vec3::new(20.0, 20.0, 1.0);
+        let tiles_width = num_columns as f32 * (tile_size.x + tile_spacing) - tile_spacing;
+        let tiles_height = num_rows as f32 * (tile_size.y + tile_spacing) - tile_spacing;
+        // center the tiles
+        let origin = Vec3::new(
+            -(tiles_width - tile_size.x) / 2.0,
+            -(tiles_height - tile_size.y) / 2.0,
+            0.0,
+        );
+
+        MapLayout {
+            tile_size,
+            tile_spacing,
+            origin,
+            num_rows,
+            num_columns,
+        }
+    }
+
+    /// Gets the position of the center of the tile with the provided coordinates
+    fn tile_position(&self, coords: &Coordinates) -> Vec3 {
+        // the first row of the map is displayed at the top
+        let row_idx = self.num_rows - 1 - coords.y;
+        self.origin
+            + Vec3::new(
+                coords.x as f32 * (self.tile_size.x + self.tile_spacing),
+                row_idx as f32 * (self.tile_size.y + self.tile_spacing),
+                0.0,
+            )
+    }
+
+    /// Gets the coordinates of the tile at the provided position, if there is one.
+    /// The space between tiles counts as part of the tile to its left or below it, so every position maps to at most one tile.
+    fn coords_at(&self, position: Vec2) -> Option<Coordinates> {
+        let bottom_left = (self.origin - self.tile_size / 2.0).truncate();
+        let cell_size = self.tile_size.truncate() + Vec2::splat(self.tile_spacing);
+        let cell = ((position - bottom_left) / cell_size).floor();
+        if cell.x < 0.0 || cell.y < 0.0 {
+            return None;
+        }
+
+        let (column_idx, row_idx) = (cell.x as usize, cell.y as usize);
+        if column_idx >= self.num_columns || row_idx >= self.num_rows {
+            return None;
+        }
+
+        Some(Coordinates {
+            x: column_idx,
+            y: self.num_rows - 1 - row_idx,
+        })
+    }
+}
+
+/// Sets up the game screen with a new map generated for the provided level
 fn set_up_game(
     commands: &mut Commands,
     asset_server: &AssetServer,
     colors: &Colors,
     level: &mut Level,
     score: &Score,
+    brush_size: &BrushSize,
+) {
+    let map = Map::generate(level);
+    set_up_game_with_map(
+        commands,
+        asset_server,
+        colors,
+        level,
+        score,
+        brush_size,
+        map,
+    );
+}
+
+/// Sets up the game screen with the provided map
+fn set_up_game_with_map(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    colors: &Colors,
+    level: &mut Level,
+    score: &Score,
+    brush_size: &BrushSize,
+    map: Map,
 ) {
     // set up map
     let num_rows = level.map_size;
     let num_columns = level.map_size;
-    let map = Map::generate(level);
     level.set_district_sizes(map.num_non_empty_tiles);
 
-    // spawn map display
-    let tile_spacing = 1.0;
-    let tile_size = Vec3::new(20.0, 20.0, 1.0);
-    let tiles_width = num_columns as f32 * (tile_size.x + tile_spacing) - tile_spacing;
-    let tiles_height = num_rows as f32 * (tile_size.y + tile_spacing) - tile_spacing;
-    // center the tiles
-    let tiles_offset = Vec3::new(
-        -(tiles_width - tile_size.x) / 2.0,
-        -(tiles_height - tile_size.y) / 2.0,
-        0.0,
-    );
+    let layout = MapLayout::new(num_rows, num_columns);
+    let timer = ElectionTimer {
+        remaining: level.time_limit,
+    };
     let font = asset_server.load(MAIN_FONT);
     let mono_font = asset_server.load(MONO_FONT);
-    for (row_idx, map_row) in map.tiles.iter().rev().enumerate() {
-        let y_position = row_idx as f32 * (tile_size.y + tile_spacing);
-        for (column_idx, map_tile) in map_row.iter().enumerate() {
-            let tile_position = Vec3::new(
-                column_idx as f32 * (tile_size.x + tile_spacing),
-                y_position,
-                0.0,
-            ) + tiles_offset;
-            commands
-                .spawn_bundle(SpriteBundle {
-                    sprite: Sprite {
-                        color: map_tile.color(colors),
-                        ..Default::default()
-                    },
-                    transform: Transform {
-                        translation: tile_position,
-                        scale: tile_size,
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                })
-                .insert(GameComponent)
-                .insert(map_tile.coords.clone())
-                .with_children(|parent| {
-                    parent
-                        .spawn_bundle(Text2dBundle {
-                            text: Text::with_section(
-                                "",
-                                TextStyle {
-                                    font: mono_font.clone(),
-                                    font_size: 25.0,
-                                    color: Color::GREEN,
-                                },
-                                Default::default(),
-                            ),
-                            transform: Transform {
-                                translation: Vec3::new(-0.3, 0.6, 2.0),
-                                scale: Vec3::new(0.05, 0.05, 1.0),
-                                ..Default::default()
-                            },
-                            ..Default::default()
-                        })
-                        .insert(map_tile.coords.clone());
-
-                    // top border
-                    parent
-                        .spawn_bundle(SpriteBundle {
-                            sprite: Sprite {
-                                color: BORDER_COLOR,
-                                ..Default::default()
-                            },
-                            transform: Transform {
-                                translation: Vec3::new(0.0, 0.5, 3.0),
-                                scale: Vec3::new(1.2, 0.2, 1.0),
-                                ..Default::default()
-                            },
-                            ..Default::default()
-                        })
-                        .insert(Border::Top);
-
-                    // bottom border
-                    parent
-                        .spawn_bundle(SpriteBundle {
-                            sprite: Sprite {
-                                color: BORDER_COLOR,
-                                ..Default::default()
-                            },
-                            transform: Transform {
-                                translation: Vec3::new(0.0, -0.5, 3.0),
-                                scale: Vec3::new(1.2, 0.2, 1.0),
-                                ..Default::default()
-                            },
-                            ..Default::default()
-                        })
-                        .insert(Border::Bottom);
-
-                    // left border
-                    parent
-                        .spawn_bundle(SpriteBundle {
-                            sprite: Sprite {
-                                color: BORDER_COLOR,
-                                ..Default::default()
-                            },
-                            transform: Transform {
-                                translation: Vec3::new(-0.5, 0.0, 3.0),
-                                scale: Vec3::new(0.2, 1.2, 1.0),
-                                ..Default::default()
-                            },
-                            ..Default::default()
-                        })
-                        .insert(Border::Left);
-
-                    // right border
-                    parent
-                        .spawn_bundle(SpriteBundle {
-                            sprite: Sprite {
-                                color: BORDER_COLOR,
-                                ..Default::default()
-                            },
-                            transform: Transform {
-                                translation: Vec3::new(0.5, 0.0, 3.0),
-                                scale: Vec3::new(0.2, 1.2, 1.0),
-                                ..Default::default()
-                            },
-                            ..Default::default()
-                        })
-                        .insert(Border::Right);
-                });
-        }
-    }
 
-    // spawn district selection buttons
+    // spawn district selection buttons, in a column that scrolls when they don't all fit
     commands
         .spawn_bundle(NodeBundle {
             style: Style {
-                size: Size::new(Val::Percent(50.0), Val::Percent(100.0)),
+                size: Size::new(Val::Px(SELECTOR_COLUMN_WIDTH), Val::Percent(100.0)),
                 position_type: PositionType::Absolute,
                 position: Rect {
                     left: Val::Px(3.0),
                     ..Default::default()
                 },
-                justify_content: JustifyContent::Center,
+                justify_content: JustifyContent::FlexStart,
                 align_items: AlignItems::FlexStart,
                 flex_direction: FlexDirection::ColumnReverse,
+                overflow: Overflow::Hidden,
                 ..Default::default()
             },
             color: UiColor(Color::NONE),
             ..Default::default()
         })
         .insert(GameComponent)
+        .insert(SelectorColumn)
         .with_children(|parent| {
-            for district_id in 0..level.districts {
-                parent
-                    .spawn_bundle(ButtonBundle {
-                        style: Style {
-                            size: Size::new(Val::Px(100.0), Val::Px(50.0)),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            margin: Rect::all(Val::Px(5.0)),
-                            ..Default::default()
-                        },
-                        color: NORMAL_BUTTON.into(),
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::FlexStart,
+                        flex_direction: FlexDirection::ColumnReverse,
                         ..Default::default()
-                    })
-                    .insert(DistrictSelector(district_id))
-                    .with_children(|parent| {
-                        parent.spawn_bundle(TextBundle {
-                            text: Text::with_section(
-                                format!("District {}", district_id + 1),
-                                TextStyle {
-                                    font: mono_font.clone(),
-                                    font_size: 20.0,
-                                    color: Color::SEA_GREEN,
-                                },
-                                Default::default(),
-                            ),
-                            ..Default::default()
-                        });
-                    });
-            }
+                    },
+                    color: UiColor(Color::NONE),
+                    ..Default::default()
+                })
+                .insert(SelectorList)
+                .with_children(|parent| {
+                    for district_id in 0..level.districts {
+                        spawn_button(
+                            parent,
+                            &mono_font,
+                            &format!("District {}", district_id + 1),
+                            WIDE_BUTTON,
+                            DistrictSelector(district_id),
+                        );
+                    }
+
+                    spawn_history_buttons(parent, &mono_font);
+                    spawn_tool_palette(parent, &mono_font, brush_size);
+                    spawn_camera_buttons(parent, &mono_font);
+                    spawn_balance_buttons(parent, &mono_font);
+                    spawn_view_buttons(parent, &mono_font);
+                    spawn_export_button(parent, &mono_font);
+                });
         });
 
     //spawn score display and level info
@@ -602,130 +1132,257 @@ fn set_up_game(
                 ..Default::default()
             });
 
+            spawn_election_timer(parent, &font, &timer);
+
             let num_good_tiles = map.get_tiles_with_content(MapTileContent::Good).len();
 
+            let text_style = TextStyle {
+                font: font.clone(),
+                font_size: 30.0,
+                color: Color::SEA_GREEN,
+            };
             parent.spawn_bundle(TextBundle {
-                text: Text::with_section(
-                    format!(
-                        "You are in the {} party.\n{}% of voters will vote for your party.\nDraw {} districts with {} to {} voters each.",
-                        colors.good_color_name,
-                        ((num_good_tiles as f32 / map.num_non_empty_tiles as f32) * 100.0).round() as u32,
-                        level.districts,
-                        level.min_district_size,
-                        level.max_district_size,
-                    ),
-                    TextStyle {
-                        font: font.clone(),
-                        font_size: 30.0,
-                        color: Color::SEA_GREEN,
-                    },
-                    TextAlignment {
+                text: Text {
+                    sections: vec![
+                        TextSection {
+                            value: party_text(colors),
+                            style: text_style.clone(),
+                        },
+                        TextSection {
+                            value: format!(
+                                "{}% of voters will vote for your party.\nDraw {} districts with {} to {} voters each.\n{}\n{} Tied districts: {}.",
+                                ((num_good_tiles as f32 / map.num_non_empty_tiles as f32) * 100.0).round() as u32,
+                                level.districts,
+                                level.min_district_size,
+                                level.max_district_size,
+                                level.tolerance.description(level.ideal_district_size(map.num_non_empty_tiles)),
+                                level.empty_tile_rule.description(),
+                                level.tie_rule.description(),
+                            ),
+                            style: text_style,
+                        },
+                    ],
+                    alignment: TextAlignment {
                         horizontal: HorizontalAlign::Center,
                         ..Default::default()
-                    }
-                ),
+                    },
+                },
                 style: Style {
                     justify_content: JustifyContent::FlexEnd,
                     ..Default::default()
                 },
                 ..Default::default()
-            });
+            })
+            .insert(PartyText);
         });
 
+    // spawn results summary
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(25.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    right: Val::Px(3.0),
+                    ..Default::default()
+                },
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::FlexEnd,
+                flex_direction: FlexDirection::ColumnReverse,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(GameComponent)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: mono_font.clone(),
+                            font_size: 20.0,
+                            color: Color::SEA_GREEN,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(SummaryText);
+        });
+
+    spawn_tooltip(commands, &mono_font);
+
     commands.insert_resource(map);
+    commands.insert_resource(layout);
+    commands.insert_resource(timer);
+    commands.insert_resource(History::default());
+    commands.insert_resource(AutoBalance::default());
+    commands.insert_resource(SplitLine::default());
+    commands.insert_resource(RectangleDrag::default());
+    commands.insert_resource(KeyboardCursor::default());
+    commands.insert_resource(SelectorScroll::default());
 }
 
-/// Sets up the main game screen.
+/// Sets up the main game screen, picking the saved run back up if the player chose to continue it.
+#[allow(clippy::too_many_arguments)]
 fn game_setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    colors: Res<Colors>,
+    mut colors: ResMut<Colors>,
     mut level: ResMut<Level>,
-    score: Res<Score>,
+    mut score: ResMut<Score>,
+    mut stats: ResMut<RunStats>,
+    mut selected_district: ResMut<SelectedDistrict>,
+    mut resume_run: ResMut<ResumeRun>,
+    brush_size: Res<BrushSize>,
 ) {
-    set_up_game(&mut commands, &asset_server, &colors, &mut level, &score);
+    let saved_run = if resume_run.0 { load_run() } else { None };
+    resume_run.0 = false;
+    if let Some(saved_run) = saved_run {
+        *colors = Colors::new(saved_run.party, colors.palette, colors.patterns);
+        *level = saved_run.level;
+        score.0 = saved_run.score;
+        *stats = saved_run.stats;
+        selected_district.0 = saved_run.selected_district;
+        set_up_game_with_map(
+            &mut commands,
+            &asset_server,
+            &colors,
+            &mut level,
+            &score,
+            &brush_size,
+            saved_run.map,
+        );
+        // replace the full timer the level was set up with
+        commands.insert_resource(ElectionTimer {
+            remaining: saved_run.time_remaining,
+        });
+        return;
+    }
+
+    level.seed = rand::thread_rng().gen();
+    set_up_game(
+        &mut commands,
+        &asset_server,
+        &colors,
+        &mut level,
+        &score,
+        &brush_size,
+    );
+}
+
+fn party_text(colors: &Colors) -> String {
+    format!("You are in the {} party.\n", colors.good_color_name)
+}
+
+/// Handles keeping the party's color name up to date when the palette changes
+fn party_text_system(colors: Res<Colors>, mut query: Query<&mut Text, With<PartyText>>) {
+    if !colors.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = party_text(&colors);
+    }
 }
 
 /// Handles interactions with map tiles
+#[allow(clippy::too_many_arguments)]
 fn tile_click_system(
-    buttons: Res<Input<MouseButton>>,
+    paint_input: Res<PaintInput>,
     cursor_position: Res<CursorPosition>,
-    selected_district: ResMut<SelectedDistrict>,
-    colors: Res<Colors>,
+    mut selected_district: ResMut<SelectedDistrict>,
+    selected_tool: Res<SelectedTool>,
+    brush_size: Res<BrushSize>,
+    mut rectangle_drag: ResMut<RectangleDrag>,
+    mut split_line: ResMut<SplitLine>,
+    mut stroke: ResMut<Stroke>,
     mut map: ResMut<Map>,
-    mut query: Query<(&Transform, &Coordinates, &mut Sprite, &Children)>,
-    mut query_child: Query<&mut Text>,
+    level: Res<Level>,
+    mut history: ResMut<History>,
+    layout: Res<MapLayout>,
 ) {
-    if buttons.pressed(MouseButton::Left) || buttons.pressed(MouseButton::Right) {
-        if let Some(pos) = cursor_position.0 {
-            for (transform, coords, mut sprite, children) in query.iter_mut() {
-                if intersects(pos, transform) {
-                    let mut tile = map.get_mut(coords);
-                    if buttons.pressed(MouseButton::Left) {
-                        tile.district_id = Some(selected_district.0);
-                        sprite.color = match tile.content {
-                            MapTileContent::Good => colors.good_faded,
-                            MapTileContent::Bad => colors.bad_faded,
-                            MapTileContent::Empty => EMPTY_TILE_COLOR_FADED,
-                        };
-                        for &child in children.iter() {
-                            if let Ok(mut text) = query_child.get_mut(child) {
-                                text.sections[0].value = format!("{}", selected_district.0 + 1);
-                            }
-                        }
-                    } else if buttons.pressed(MouseButton::Right) {
-                        tile.district_id = None;
-                        sprite.color = tile.color(&colors);
-                        for &child in children.iter() {
-                            if let Ok(mut text) = query_child.get_mut(child) {
-                                text.sections[0].value = "".to_string();
-                            }
-                        }
+    // everything painted from pressing a button to releasing it is undone together
+    if paint_input.just_pressed() {
+        history.begin_action();
+        *stroke = Stroke::default();
+    }
+
+    let district_id = paint_input.district_id(&selected_district);
+    let pick_tile = |pos: Vec2| layout.coords_at(pos);
+    let hovered_tile = cursor_position.0.and_then(pick_tile);
+
+    if paint_input.pressed() {
+        match selected_tool.0 {
+            Tool::Brush => {
+                let tile_size = layout.tile_size.x.min(layout.tile_size.y);
+                for coords in stroke.advance(cursor_position.0, tile_size, pick_tile) {
+                    paint_brush(&mut map, &mut history, &coords, brush_size.0, district_id);
+                }
+            }
+            Tool::FloodFill => {
+                if paint_input.just_pressed() {
+                    if let Some(coords) = hovered_tile {
+                        flood_fill(&mut map, &mut history, &coords, district_id);
+                    }
+                }
+            }
+            Tool::Rectangle => {
+                if let Some(coords) = hovered_tile {
+                    if rectangle_drag.start.is_none() {
+                        rectangle_drag.start = Some(coords.clone());
+                    }
+                    rectangle_drag.end = Some(coords);
+                }
+            }
+            Tool::Merge => {
+                if paint_input.just_pressed() && district_id.is_some() {
+                    if let Some(from) = hovered_tile.and_then(|coords| map.get(&coords).district_id)
+                    {
+                        merge_districts(&mut map, &mut history, selected_district.0, from);
+                    }
+                }
+            }
+            Tool::Split => {
+                if let Some(position) = cursor_position.0 {
+                    if split_line.start.is_none() {
+                        split_line.start = Some(position);
                     }
+                    split_line.end = Some(position);
                 }
             }
         }
-    }
-}
-
-/// Handles updating the map based on district winners
-fn map_update_system(
-    map: Res<Map>,
-    level: Res<Level>,
-    colors: Res<Colors>,
-    query: Query<(&Coordinates, &Children)>,
-    mut query_child: Query<&mut Text>,
-) {
-    let results = map.get_district_results(level.districts);
-    for (coords, children) in query.iter() {
-        let tile = map.get(coords);
-        for &child in children.iter() {
-            if let Ok(mut text) = query_child.get_mut(child) {
-                if let Some(district_id) = tile.district_id {
-                    let color = match results[district_id as usize].winner {
-                        Some(DistrictWinner::Good) => colors.good_regular,
-                        Some(DistrictWinner::Bad) => colors.bad_regular,
-                        Some(DistrictWinner::Tie) => Color::YELLOW_GREEN,
-                        None => Color::GREEN,
-                    };
-                    text.sections[0].style.color = color;
+    } else if paint_input.just_released() {
+        if let (Some(start), Some(end)) = (rectangle_drag.start.take(), rectangle_drag.end.take()) {
+            fill_rectangle(&mut map, &mut history, &start, &end, district_id);
+        }
+        if let (Some(start), Some(end)) = (split_line.start.take(), split_line.end.take()) {
+            if district_id.is_some() {
+                let split = split_district(
+                    &mut map,
+                    &level,
+                    &mut history,
+                    &layout,
+                    selected_district.0,
+                    start,
+                    end,
+                );
+                if let Some(new_district_id) = split {
+                    selected_district.0 = new_district_id;
                 }
             }
         }
+        history.end_action();
     }
 }
 
-/// Determines whether a point intersects a transform
-fn intersects(point: Vec2, transform: &Transform) -> bool {
-    point.x >= transform.translation.x - (transform.scale.x / 2.0) - 1.0
-        && point.x <= transform.translation.x + (transform.scale.x / 2.0) + 1.0
-        && point.y >= transform.translation.y - (transform.scale.y / 2.0) - 1.0
-        && point.y <= transform.translation.y + (transform.scale.y / 2.0) + 1.0
-}
-
 /// Handles selecting which district to paint
 fn district_selection_system(
     mut selected_district: ResMut<SelectedDistrict>,
+    level: Res<Level>,
+    actions: Res<Actions>,
     interaction_query: Query<(&Interaction, &DistrictSelector), Changed<Interaction>>,
     mut button_query: Query<(&DistrictSelector, &mut UiColor)>,
 ) {
@@ -735,107 +1392,55 @@ fn district_selection_system(
         }
     }
 
-    for (district_selector, mut color) in button_query.iter_mut() {
-        if selected_district.0 == district_selector.0 {
-            *color = Color::WHITE.into();
-        } else {
-            *color = NORMAL_BUTTON.into();
+    for (district_id, action) in Action::SELECT_DISTRICT.iter().enumerate() {
+        if actions.just_pressed(*action) && district_id < level.districts as usize {
+            selected_district.0 = district_id as u8;
         }
     }
-}
-
-/// Handles showing district borders
-fn border_system(
-    map: Res<Map>,
-    query: Query<(&Coordinates, &Children)>,
-    mut query_child: Query<(&Border, &mut Sprite)>,
-) {
-    for (coords, children) in query.iter() {
-        let tile = map.get(coords);
-
-        let needs_top_border = if let Some(up_tile) = map.get_up(coords) {
-            tile.district_id != up_tile.district_id
-        } else {
-            tile.district_id.is_some()
-        };
 
-        let needs_bottom_border = if let Some(down_tile) = map.get_down(coords) {
-            tile.district_id != down_tile.district_id
-        } else {
-            tile.district_id.is_some()
-        };
-
-        let needs_left_border = if let Some(left_tile) = map.get_left(coords) {
-            tile.district_id != left_tile.district_id
-        } else {
-            tile.district_id.is_some()
-        };
+    if actions.just_pressed(Action::NextDistrict) {
+        selected_district.0 = (selected_district.0 + 1) % level.districts;
+    } else if actions.just_pressed(Action::PreviousDistrict) {
+        selected_district.0 = (selected_district.0 + level.districts - 1) % level.districts;
+    }
 
-        let needs_right_border = if let Some(right_tile) = map.get_right(coords) {
-            tile.district_id != right_tile.district_id
+    for (district_selector, mut color) in button_query.iter_mut() {
+        if selected_district.0 == district_selector.0 {
+            *color = Color::WHITE.into();
         } else {
-            tile.district_id.is_some()
-        };
-
-        for &child in children.iter() {
-            if let Ok((border, mut sprite)) = query_child.get_mut(child) {
-                if matches!(border, Border::Top) {
-                    if needs_top_border {
-                        sprite.color.set_a(1.0);
-                    } else {
-                        sprite.color.set_a(0.0);
-                    }
-                }
-
-                if matches!(border, Border::Bottom) {
-                    if needs_bottom_border {
-                        sprite.color.set_a(1.0);
-                    } else {
-                        sprite.color.set_a(0.0);
-                    }
-                }
-
-                if matches!(border, Border::Left) {
-                    if needs_left_border {
-                        sprite.color.set_a(1.0);
-                    } else {
-                        sprite.color.set_a(0.0);
-                    }
-                }
-
-                if matches!(border, Border::Right) {
-                    if needs_right_border {
-                        sprite.color.set_a(1.0);
-                    } else {
-                        sprite.color.set_a(0.0);
-                    }
-                }
-            }
+            *color = NORMAL_BUTTON.into();
         }
     }
 }
 
 /// Handles displaying info about the current districts
 fn district_info_system(
-    map: Res<Map>,
     level: Res<Level>,
+    results: Res<DistrictResults>,
     button_query: Query<(&DistrictSelector, &Children)>,
     mut query_child: Query<&mut Text>,
 ) {
-    let results = map.get_district_results(level.districts);
+    if !results.is_changed() {
+        return;
+    }
+
     for (district_selector, children) in button_query.iter() {
         for &child in children.iter() {
             if let Ok(mut text) = query_child.get_mut(child) {
-                let result = &results[district_selector.0 as usize];
+                let result = &results.0[district_selector.0 as usize];
                 let validity_text = match result.validity(&level) {
-                    DistrictValidity::TooBig => " [too big]",
-                    DistrictValidity::TooSmall => " [too small]",
-                    DistrictValidity::NonContiguous => " [non-contiguous]",
+                    DistrictValidity::TooBig => " [too big]".to_string(),
+                    DistrictValidity::TooSmall => " [too small]".to_string(),
+                    DistrictValidity::NonContiguous => " [non-contiguous]".to_string(),
                     DistrictValidity::Valid => match result.winner {
-                        Some(DistrictWinner::Good) => " [win]",
-                        Some(DistrictWinner::Bad) => " [lose]",
-                        Some(DistrictWinner::Tie) => " [tie]",
-                        None => " [invalid]",
+                        Some(DistrictWinner::Good) => " [win]".to_string(),
+                        Some(DistrictWinner::Bad) => " [lose]".to_string(),
+                        Some(DistrictWinner::Tie) => format!(
+                            " [tie, {} by {}]",
+                            tie_outcome(result),
+                            level.tie_rule.description()
+                        ),
+                        None => " [invalid]".to_string(),
                     },
                 };
                 text.sections[0].value = format!(
@@ -848,48 +1453,111 @@ fn district_info_system(
     }
 }
 
-/// Handles determining whether the level is solved
-fn solution_system(mut solved: ResMut<Solved>, map: Res<Map>, level: Res<Level>) {
-    let results = map.get_district_results(level.districts);
+/// Describes how a tied district was decided for the good party
+fn tie_outcome(result: &DistrictResult) -> &'static str {
+    if result.seat_winner() == Some(DistrictWinner::Good) {
+        "won"
+    } else {
+        "lost"
+    }
+}
+
+/// Handles displaying a summary of the seats each party would win
+fn summary_system(
+    level: Res<Level>,
+    results: Res<DistrictResults>,
+    mut query: Query<&mut Text, With<SummaryText>>,
+) {
+    if !results.is_changed() {
+        return;
+    }
+
+    let seats_for = |party| {
+        results
+            .0
+            .iter()
+            .filter(|result| result.validity(&level) == DistrictValidity::Valid)
+            .filter(|result| result.seat_winner() == Some(party))
+            .count()
+    };
+
+    let mut summary = format!(
+        "Seats won: {}\nSeats lost: {}\nNeeded to win: {}",
+        seats_for(DistrictWinner::Good),
+        seats_for(DistrictWinner::Bad),
+        level.districts / 2 + 1,
+    );
+    for (district_id, result) in results.0.iter().enumerate() {
+        if result.winner == Some(DistrictWinner::Tie) {
+            summary.push_str(&format!(
+                "\nDistrict {} tied,\n  {} by {}",
+                district_id + 1,
+                tie_outcome(result),
+                level.tie_rule.description()
+            ));
+        }
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = summary.clone();
+    }
+}
+
+/// Handles keeping the cached district results up to date
+fn district_results_system(map: Res<Map>, level: Res<Level>, mut results: ResMut<DistrictResults>) {
+    if map.is_changed() || level.is_changed() {
+        results.0 = map.get_district_results(&level);
+    }
+}
+
+/// Handles determining whether the plan is complete, and whether it's solved
+fn solution_system(
+    mut solved: ResMut<Solved>,
+    mut plan_complete: ResMut<PlanComplete>,
+    map: Res<Map>,
+    level: Res<Level>,
+    results: Res<DistrictResults>,
+) {
+    if !results.is_changed() {
+        return;
+    }
 
     // make sure all districts are the right size and have a winner
     let any_invalid_districts = results
+        .0
         .iter()
         .any(|result| result.validity(&level) != DistrictValidity::Valid);
     if any_invalid_districts {
         solved.0 = false;
+        plan_complete.0 = false;
         return;
     }
 
     // make sure all tiles are in a district
-    let any_districtless_tiles = map
-        .tiles
-        .iter()
-        .any(|row| row.iter().any(|tile| tile.district_id == None));
-    if any_districtless_tiles {
+    if !map.all_tiles_assigned(&level) {
         solved.0 = false;
+        plan_complete.0 = false;
         return;
     }
 
+    plan_complete.0 = true;
+
     let good_wins = results
+        .0
         .iter()
-        .filter(|result| result.winner == Some(DistrictWinner::Good))
+        .filter(|result| result.seat_winner() == Some(DistrictWinner::Good))
         .count();
-    if good_wins as f32 > (level.districts as f32 / 2.0) {
-        solved.0 = true;
-    } else {
-        solved.0 = false;
-    }
+    solved.0 = good_wins as f32 > (level.districts as f32 / 2.0);
 }
 
-/// Handles showing and hiding the confirm button
+/// Handles showing and hiding the confirm button, which is available for any complete plan, even a losing one
 fn confirm_button_visibility_system(
-    solved: Res<Solved>,
+    plan_complete: Res<PlanComplete>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut query: Query<&mut Style, With<ConfirmButtonParent>>,
 ) {
-    if solved.0 {
+    if plan_complete.0 {
         if query.is_empty() {
             let font = asset_server.load(MAIN_FONT);
             commands
@@ -912,33 +1580,7 @@ fn confirm_button_visibility_system(
                 .insert(GameComponent)
                 .insert(ConfirmButtonParent)
                 .with_children(|parent| {
-                    parent
-                        .spawn_bundle(ButtonBundle {
-                            style: Style {
-                                size: Size::new(Val::Px(100.0), Val::Px(50.0)),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                margin: Rect::all(Val::Px(5.0)),
-                                ..Default::default()
-                            },
-                            color: NORMAL_BUTTON.into(),
-                            ..Default::default()
-                        })
-                        .insert(ConfirmButton)
-                        .with_children(|parent| {
-                            parent.spawn_bundle(TextBundle {
-                                text: Text::with_section(
-                                    "Confirm",
-                                    TextStyle {
-                                        font,
-                                        font_size: 20.0,
-                                        color: Color::SEA_GREEN,
-                                    },
-                                    Default::default(),
-                                ),
-                                ..Default::default()
-                            });
-                        });
+                    spawn_button(parent, &font, "Confirm", WIDE_BUTTON, ConfirmButton);
                 });
         } else {
             for mut style in query.iter_mut() {
@@ -955,38 +1597,95 @@ fn confirm_button_visibility_system(
 type InteractedConfirmButtonTuple = (Changed<Interaction>, With<ConfirmButton>);
 
 /// Handles interactions with the confirm button.
-#[allow(clippy::too_many_arguments)]
 fn confirm_button_system(
+    plan_complete: Res<PlanComplete>,
+    timer: Res<ElectionTimer>,
+    level: Res<Level>,
+    mut map: ResMut<Map>,
+    mut game_state: ResMut<State<GameState>>,
+    actions: Res<Actions>,
+    interaction_query: Query<&Interaction, InteractedConfirmButtonTuple>,
+) {
+    let mut confirmed = plan_complete.0 && actions.just_pressed(Action::Confirm);
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            confirmed = true;
+            break;
+        }
+    }
+
+    // once time runs out, the election is already being held
+    if confirmed && !timer.expired() {
+        if level.empty_tile_rule == EmptyTileRule::AutoAssign {
+            map.fill_empty_tiles();
+        }
+        game_state.push(GameState::Results).unwrap();
+    }
+}
+
+/// Adds the results of the election that was just held to the run's stats
+fn record_election_system(
+    reveal: Res<ResultsReveal>,
+    level: Res<Level>,
+    mut stats: ResMut<RunStats>,
+) {
+    stats.record_election(reveal.results(), &level);
+}
+
+/// Moves on to the next level once the results of the current one have been shown, if the election was won
+#[allow(clippy::too_many_arguments)]
+fn next_level_system(
+    reveal: Res<ResultsReveal>,
+    timer: Res<ElectionTimer>,
     mut level: ResMut<Level>,
     mut score: ResMut<Score>,
     mut solved: ResMut<Solved>,
     mut selected_district: ResMut<SelectedDistrict>,
+    brush_size: Res<BrushSize>,
     asset_server: Res<AssetServer>,
     colors: Res<Colors>,
     mut commands: Commands,
-    interaction_query: Query<&Interaction, InteractedConfirmButtonTuple>,
     to_despawn_query: Query<Entity, With<GameComponent>>,
 ) {
-    let mut change_level = false;
-    for interaction in interaction_query.iter() {
-        if *interaction == Interaction::Clicked {
-            change_level = true;
-            break;
-        }
+    // losing the election ends the run instead
+    if !reveal.majority_won(&level) {
+        return;
     }
 
-    if change_level {
-        score.0 += 10;
-        *level = generate_next_level(&level);
-        solved.0 = false;
-        selected_district.0 = 0;
-        despawn_components(to_despawn_query, &mut commands);
-        set_up_game(&mut commands, &asset_server, &colors, &mut level, &score);
-    }
+    score.0 += timer.years_for_win();
+    *level = generate_next_level(&level, reveal.results());
+    solved.0 = false;
+    selected_district.0 = 0;
+    despawn_components(to_despawn_query, &mut commands);
+    set_up_game(
+        &mut commands,
+        &asset_server,
+        &colors,
+        &mut level,
+        &score,
+        &brush_size,
+    );
+}
+
+/// Handles resetting everything about the run once it's over, so the next one starts from the beginning
+fn reset_run_system(
+    mut level: ResMut<Level>,
+    mut score: ResMut<Score>,
+    mut stats: ResMut<RunStats>,
+    mut solved: ResMut<Solved>,
+    mut plan_complete: ResMut<PlanComplete>,
+    mut selected_district: ResMut<SelectedDistrict>,
+) {
+    *level = STARTING_LEVEL;
+    score.0 = 0;
+    *stats = RunStats::default();
+    solved.0 = false;
+    plan_complete.0 = false;
+    selected_district.0 = 0;
 }
 
-/// Generates the next level using the previous level as a baseline
-fn generate_next_level(old_level: &Level) -> Level {
+/// Generates the next level using the previous level and the results of its election as a baseline
+fn generate_next_level(old_level: &Level, results: &[DistrictResult]) -> Level {
     let map_size = old_level.map_size + 1;
     // ensure an odd number of districts to make the game easier
     // (so you only have to win 1 more district than the bad party instead of 2)
@@ -996,12 +1695,23 @@ fn generate_next_level(old_level: &Level) -> Level {
     };
     let populated_pct = old_level.populated_pct * 1.05;
     let avg_district_size = (map_size as f32 * map_size as f32 * populated_pct) / districts as f32;
+    let tolerance = PopulationTolerance::for_map_size(map_size);
+    let (min_district_size, max_district_size) = tolerance.district_size_range(avg_district_size);
+    // seeded by the current level, so the next level comes out the same however many times it's generated
+    let mut rng = StdRng::seed_from_u64(old_level.seed);
     Level {
         districts,
         good_pct: old_level.good_pct * 0.8,
         populated_pct,
         map_size,
-        min_district_size: (avg_district_size * 0.95).round() as usize,
-        max_district_size: (avg_district_size * 1.05).round() as usize,
+        min_district_size,
+        max_district_size,
+        tolerance,
+        empty_tile_rule: EmptyTileRule::random(&mut rng),
+        tie_rule: TieRule::random(&mut rng),
+        // whoever won a majority of the seats in the last election is now in power
+        good_is_incumbent: majority_of_seats(results, old_level),
+        time_limit: time_limit_for_map_size(map_size),
+        seed: rng.gen(),
     }
 }
@@ -0,0 +1,332 @@
+use super::*;
+
+/// How many possible moves auto-balancing checks each frame
+const BALANCE_CHECKS_PER_FRAME: usize = 32;
+const BALANCE_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(95.0),
+        height: Val::Px(40.0),
+    },
+    margin: 5.0,
+    font_size: 20.0,
+};
+
+/// Whether the auto-balancer is allowed to change which party wins a district
+pub struct AllowOutcomeChanges(pub bool);
+
+#[derive(Component)]
+pub struct BalanceButton;
+
+#[derive(Component)]
+pub struct AllowOutcomeChangesButton;
+
+/// Determines how far the provided district size is outside the level's allowed range
+fn size_deviation(size: usize, level: &Level) -> usize {
+    if size < level.min_district_size {
+        level.min_district_size - size
+    } else {
+        size.saturating_sub(level.max_district_size)
+    }
+}
+
+/// Determines which party would get the seat for the provided district, ignoring whether it's valid
+fn seat_winner(map: &Map, level: &Level, district_id: u8) -> Option<DistrictWinner> {
+    let tiles = map.get_tiles_in_district(district_id);
+    if tiles.is_empty() {
+        return None;
+    }
+
+    let votes_for = |content| tiles.iter().filter(|tile| tile.content == content).count();
+    match votes_for(MapTileContent::Good).cmp(&votes_for(MapTileContent::Bad)) {
+        Ordering::Greater => Some(DistrictWinner::Good),
+        Ordering::Less => Some(DistrictWinner::Bad),
+        Ordering::Equal => Some(level.tie_rule.decide(level, district_id, &tiles)),
+    }
+}
+
+/// Determines whether the provided district would still be in one piece without the tile with the provided coordinates
+fn contiguous_without(map: &Map, district_id: u8, removed: &Coordinates) -> bool {
+    let remaining = map
+        .get_tiles_in_district(district_id)
+        .into_iter()
+        .filter(|tile| tile.coords != *removed)
+        .map(|tile| tile.coords.clone())
+        .collect::<HashSet<Coordinates>>();
+    let start = match remaining.iter().next() {
+        Some(start) => start.clone(),
+        None => return false,
+    };
+
+    let mut reached = HashSet::new();
+    let mut to_visit = vec![start];
+    while let Some(coords) = to_visit.pop() {
+        if !remaining.contains(&coords) || !reached.insert(coords.clone()) {
+            continue;
+        }
+        to_visit.extend(
+            [
+                map.get_up(&coords),
+                map.get_down(&coords),
+                map.get_left(&coords),
+                map.get_right(&coords),
+            ]
+            .into_iter()
+            .flatten()
+            .map(|tile| tile.coords.clone()),
+        );
+    }
+
+    reached.len() == remaining.len()
+}
+
+/// A tile that could be moved from one district to a neighboring one
+struct BalanceMove {
+    coords: Coordinates,
+    from: u8,
+    to: u8,
+    /// How much the move brings districts back into the allowed size range
+    deviation_reduction: isize,
+    /// How much the move evens out district sizes overall
+    spread_reduction: f32,
+}
+
+/// An auto-balance in progress. Balancing a big map can take many moves, so they're spread over several frames.
+pub struct BalanceJob {
+    /// Which party each district went to before balancing started, so outcomes can be kept the same
+    original_winners: Vec<Option<DistrictWinner>>,
+    allow_outcome_changes: bool,
+    /// How many more moves can be made before giving up
+    moves_left: usize,
+    /// The moves that have been ruled out since the last move was made
+    rejected: HashSet<(Coordinates, u8)>,
+    /// The changes made so far, which are recorded in the history as a single action once balancing is done
+    changes: Vec<TileChange>,
+}
+
+/// The auto-balance in progress, if there is one
+#[derive(Default)]
+pub struct AutoBalance(pub Option<BalanceJob>);
+
+impl BalanceJob {
+    /// Starts balancing the provided map.
+    /// Border tiles are moved between neighboring districts until every district is within the level's size limits, or no more useful moves can be made.
+    /// Districts are kept contiguous, and unless `allow_outcome_changes` is set, no district changes which party wins it.
+    pub fn new(map: &Map, level: &Level, allow_outcome_changes: bool) -> Self {
+        BalanceJob {
+            original_winners: (0..level.districts)
+                .map(|district_id| seat_winner(map, level, district_id))
+                .collect(),
+            allow_outcome_changes,
+            moves_left: map.num_non_empty_tiles,
+            rejected: HashSet::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// Considers up to `max_checks` moves. Returns whether balancing is finished.
+    fn step(&mut self, map: &mut Map, level: &Level, max_checks: usize) -> bool {
+        let ideal_size = level.ideal_district_size(map.num_non_empty_tiles);
+        let mut checks = 0;
+        loop {
+            if self.moves_left == 0 {
+                return true;
+            }
+
+            let sizes = (0..level.districts)
+                .map(|district_id| {
+                    map.get_tiles_in_district(district_id)
+                        .iter()
+                        .filter(|tile| tile.content != MapTileContent::Empty)
+                        .count()
+                })
+                .collect::<Vec<usize>>();
+            if sizes.iter().all(|size| size_deviation(*size, level) == 0) {
+                return true;
+            }
+
+            let mut moves = Vec::new();
+            for tile in map.tiles.iter().flatten() {
+                let from = match tile.district_id {
+                    Some(from) if tile.content != MapTileContent::Empty => from,
+                    _ => continue,
+                };
+                let neighbor_districts = [
+                    map.get_up(&tile.coords),
+                    map.get_down(&tile.coords),
+                    map.get_left(&tile.coords),
+                    map.get_right(&tile.coords),
+                ]
+                .into_iter()
+                .flatten()
+                .filter_map(|neighbor| neighbor.district_id)
+                .filter(|to| *to != from)
+                .collect::<HashSet<u8>>();
+
+                for to in neighbor_districts {
+                    if self.rejected.contains(&(tile.coords.clone(), to)) {
+                        continue;
+                    }
+                    let (from_size, to_size) = (sizes[from as usize], sizes[to as usize]);
+                    let deviation_before =
+                        size_deviation(from_size, level) + size_deviation(to_size, level);
+                    let deviation_after =
+                        size_deviation(from_size - 1, level) + size_deviation(to_size + 1, level);
+                    let spread = |size: usize| (size as f32 - ideal_size).powi(2);
+                    moves.push(BalanceMove {
+                        coords: tile.coords.clone(),
+                        from,
+                        to,
+                        deviation_reduction: deviation_before as isize - deviation_after as isize,
+                        spread_reduction: spread(from_size) + spread(to_size)
+                            - spread(from_size - 1)
+                            - spread(to_size + 1),
+                    });
+                }
+            }
+
+            // only make moves that help, best first
+            moves.retain(|balance_move| {
+                balance_move.deviation_reduction > 0
+                    || (balance_move.deviation_reduction == 0
+                        && balance_move.spread_reduction > 0.0)
+            });
+            moves.sort_by(|a, b| {
+                b.deviation_reduction.cmp(&a.deviation_reduction).then(
+                    b.spread_reduction
+                        .partial_cmp(&a.spread_reduction)
+                        .unwrap_or(Ordering::Equal),
+                )
+            });
+
+            let mut moved = false;
+            for balance_move in moves {
+                if checks == max_checks {
+                    return false;
+                }
+                checks += 1;
+
+                if self.try_move(map, level, &balance_move) {
+                    moved = true;
+                    break;
+                }
+                self.rejected.insert((balance_move.coords, balance_move.to));
+            }
+
+            // every helpful move has been ruled out
+            if !moved {
+                return true;
+            }
+        }
+    }
+
+    /// Makes the provided move if it keeps districts contiguous and doesn't change any outcomes it isn't allowed to.
+    /// Returns whether the move was made.
+    fn try_move(&mut self, map: &mut Map, level: &Level, balance_move: &BalanceMove) -> bool {
+        if !contiguous_without(map, balance_move.from, &balance_move.coords) {
+            return false;
+        }
+
+        if !self.allow_outcome_changes {
+            // try the move out without recording it, then put the tile back
+            map.get_mut(&balance_move.coords).district_id = Some(balance_move.to);
+            let outcome_changed = [balance_move.from, balance_move.to]
+                .iter()
+                .any(|district_id| {
+                    seat_winner(map, level, *district_id)
+                        != self.original_winners[*district_id as usize]
+                });
+            map.get_mut(&balance_move.coords).district_id = Some(balance_move.from);
+            if outcome_changed {
+                return false;
+            }
+        }
+
+        if let Some(change) = TileChange::apply(map, &balance_move.coords, Some(balance_move.to)) {
+            self.changes.push(change);
+        }
+        self.moves_left -= 1;
+        self.rejected.clear();
+        true
+    }
+}
+
+/// Spawns the auto-balance buttons
+pub fn spawn_balance_buttons(parent: &mut ChildBuilder, font: &Handle<Font>) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_button(parent, font, "Balance", BALANCE_BUTTON, BalanceButton);
+            spawn_button(
+                parent,
+                font,
+                "Unlock",
+                BALANCE_BUTTON,
+                AllowOutcomeChangesButton,
+            );
+        });
+}
+
+type InteractedBalanceButtonTuple = (Changed<Interaction>, With<BalanceButton>);
+type InteractedAllowOutcomeChangesButtonTuple =
+    (Changed<Interaction>, With<AllowOutcomeChangesButton>);
+
+/// Handles interactions with the auto-balance buttons
+pub fn balance_button_system(
+    map: Res<Map>,
+    level: Res<Level>,
+    mut auto_balance: ResMut<AutoBalance>,
+    mut allow_outcome_changes: ResMut<AllowOutcomeChanges>,
+    balance_query: Query<&Interaction, InteractedBalanceButtonTuple>,
+    allow_query: Query<&Interaction, InteractedAllowOutcomeChangesButtonTuple>,
+    mut allow_button_query: Query<&mut UiColor, With<AllowOutcomeChangesButton>>,
+) {
+    if allow_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked)
+    {
+        allow_outcome_changes.0 = !allow_outcome_changes.0;
+    }
+
+    for mut color in allow_button_query.iter_mut() {
+        if allow_outcome_changes.0 {
+            *color = Color::WHITE.into();
+        } else {
+            *color = NORMAL_BUTTON.into();
+        }
+    }
+
+    let clicked = balance_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked && auto_balance.0.is_none() {
+        auto_balance.0 = Some(BalanceJob::new(&map, &level, allow_outcome_changes.0));
+    }
+}
+
+/// Handles making progress on the auto-balance in progress, a few moves at a time so big maps don't freeze the game
+pub fn auto_balance_system(
+    mut map: ResMut<Map>,
+    level: Res<Level>,
+    mut history: ResMut<History>,
+    mut auto_balance: ResMut<AutoBalance>,
+) {
+    let job = match &mut auto_balance.0 {
+        Some(job) => job,
+        None => return,
+    };
+
+    // stop if the player changed any of the balanced tiles in the meantime, so the history stays consistent
+    let interrupted = !job.changes.iter().all(|change| change.still_applied(&map));
+    if interrupted || job.step(&mut map, &level, BALANCE_CHECKS_PER_FRAME) {
+        if let Some(job) = auto_balance.0.take() {
+            history.record_action(job.changes);
+        }
+    }
+}
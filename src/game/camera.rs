@@ -0,0 +1,183 @@
+use bevy::{
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    window::WindowResized,
+};
+
+use super::*;
+
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 8.0;
+/// How much one notch of the mouse wheel zooms by
+const WHEEL_ZOOM_FACTOR: f32 = 1.1;
+/// How fast the camera pans with the keyboard, in pixels per second at normal zoom
+const PAN_SPEED: f32 = 500.0;
+/// The horizontal space to leave around the map for the UI when fitting it on screen, in pixels at the normal UI scale
+const FIT_MARGIN_X: f32 = 500.0;
+/// The vertical space to leave around the map for the UI when fitting it on screen, in pixels at the normal UI scale
+const FIT_MARGIN_Y: f32 = 340.0;
+
+#[derive(Component)]
+pub struct FitMapButton;
+
+/// Spawns the button for fitting the map on screen
+pub fn spawn_camera_buttons(parent: &mut ChildBuilder, font: &Handle<Font>) {
+    spawn_button(parent, font, "Fit", SMALL_BUTTON, FitMapButton);
+}
+
+/// Moves the camera and sets its zoom so the whole map is visible, without zooming in past the normal size
+fn fit_map(
+    layout: &MapLayout,
+    window: &Window,
+    ui_scale: &UiScale,
+    transform: &mut Transform,
+    projection: &mut OrthographicProjection,
+) {
+    let map_size = Vec2::new(
+        layout.num_columns as f32 * (layout.tile_size.x + layout.tile_spacing),
+        layout.num_rows as f32 * (layout.tile_size.y + layout.tile_spacing),
+    );
+    let available_size = (Vec2::new(
+        window.width() - FIT_MARGIN_X * ui_scale.0,
+        window.height() - FIT_MARGIN_Y * ui_scale.0,
+    ))
+    .max(Vec2::ONE);
+    let scale = (map_size / available_size).max_element();
+
+    projection.scale = scale.clamp(1.0, MAX_ZOOM);
+    transform.translation.x = 0.0;
+    transform.translation.y = 0.0;
+}
+
+/// Zooms the camera by the provided factor, keeping the provided world position in the same spot on screen
+fn zoom_camera(
+    factor: f32,
+    focus: Option<Vec2>,
+    transform: &mut Transform,
+    projection: &mut OrthographicProjection,
+) {
+    let old_scale = projection.scale;
+    projection.scale = (projection.scale * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+
+    if let Some(focus) = focus {
+        let camera_position = transform.translation.truncate();
+        let new_position = focus + (camera_position - focus) * (projection.scale / old_scale);
+        transform.translation.x = new_position.x;
+        transform.translation.y = new_position.y;
+    }
+}
+
+/// Handles zooming the camera with the mouse wheel or by pinching with two fingers
+pub fn camera_zoom_system(
+    mut wheel_events: EventReader<MouseWheel>,
+    touches: Res<Touches>,
+    cursor_position: Res<CursorPosition>,
+    selector_scroll: Res<SelectorScroll>,
+    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let mut factor = 1.0;
+    // the mouse wheel scrolls the district selector column instead when the cursor is over it
+    for event in wheel_events.iter().filter(|_| !selector_scroll.hovered) {
+        let notches = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 50.0,
+        };
+        factor *= WHEEL_ZOOM_FACTOR.powf(-notches);
+    }
+
+    let mut active_touches = touches.iter();
+    if let (Some(first), Some(second)) = (active_touches.next(), active_touches.next()) {
+        let previous_distance = first
+            .previous_position()
+            .distance(second.previous_position());
+        let distance = first.position().distance(second.position());
+        if previous_distance > 0.0 && distance > 0.0 {
+            factor *= previous_distance / distance;
+        }
+    }
+
+    if (factor - 1.0).abs() < f32::EPSILON {
+        return;
+    }
+
+    for (mut transform, mut projection) in query.iter_mut() {
+        zoom_camera(factor, cursor_position.0, &mut transform, &mut projection);
+    }
+}
+
+/// Handles panning the camera with the keyboard, by dragging while the drag to pan control is held, or by dragging with two fingers
+pub fn camera_pan_system(
+    time: Res<Time>,
+    actions: Res<Actions>,
+    touches: Res<Touches>,
+    mut motion_events: EventReader<MouseMotion>,
+    mut query: Query<(&mut Transform, &OrthographicProjection), With<MainCamera>>,
+) {
+    let mut direction = Vec2::ZERO;
+    if actions.pressed(Action::PanUp) {
+        direction.y += 1.0;
+    }
+    if actions.pressed(Action::PanDown) {
+        direction.y -= 1.0;
+    }
+    if actions.pressed(Action::PanLeft) {
+        direction.x -= 1.0;
+    }
+    if actions.pressed(Action::PanRight) {
+        direction.x += 1.0;
+    }
+    // movement in screen pixels, with y pointing up
+    let mut screen_movement = direction.normalize_or_zero() * PAN_SPEED * time.delta_seconds();
+
+    // dragging moves the map along with the cursor, so the camera moves the other way
+    let drag_movement = motion_events
+        .iter()
+        .fold(Vec2::ZERO, |total, event| total + event.delta);
+    if actions.pressed(Action::DragPan) {
+        screen_movement += Vec2::new(-drag_movement.x, drag_movement.y);
+    }
+
+    let mut active_touches = touches.iter();
+    if let (Some(first), Some(second)) = (active_touches.next(), active_touches.next()) {
+        // touch positions are measured from the top of the window, like mouse motion
+        let midpoint_movement = (first.delta() + second.delta()) / 2.0;
+        screen_movement += Vec2::new(-midpoint_movement.x, midpoint_movement.y);
+    }
+
+    if screen_movement == Vec2::ZERO {
+        return;
+    }
+
+    for (mut transform, projection) in query.iter_mut() {
+        let world_movement = screen_movement * projection.scale;
+        transform.translation.x += world_movement.x;
+        transform.translation.y += world_movement.y;
+    }
+}
+
+type InteractedFitMapButtonTuple = (Changed<Interaction>, With<FitMapButton>);
+
+/// Handles fitting the map on screen when a new map is set up, the window is resized, or the fit map button is pressed
+pub fn fit_map_system(
+    windows: Res<Windows>,
+    layout: Res<MapLayout>,
+    ui_scale: Res<UiScale>,
+    mut resized_events: EventReader<WindowResized>,
+    interaction_query: Query<&Interaction, InteractedFitMapButtonTuple>,
+    mut query: Query<(&Camera, &mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let fit_requested = layout.is_changed()
+        || ui_scale.is_changed()
+        || resized_events.iter().last().is_some()
+        || interaction_query
+            .iter()
+            .any(|interaction| *interaction == Interaction::Clicked);
+    if !fit_requested {
+        return;
+    }
+
+    for (camera, mut transform, mut projection) in query.iter_mut() {
+        if let Some(window) = windows.get(camera.window) {
+            fit_map(&layout, window, &ui_scale, &mut transform, &mut projection);
+        }
+    }
+}
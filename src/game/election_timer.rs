@@ -0,0 +1,103 @@
+use super::*;
+
+/// How much time every level gets, no matter its size
+const BASE_TIME_LIMIT_SECONDS: f32 = 30.0;
+/// How much extra time a level gets for each tile on its map
+const TIME_LIMIT_SECONDS_PER_TILE: f32 = 1.2;
+/// How many seconds have to be left over when a plan is confirmed to earn a bonus year in power
+const SECONDS_PER_BONUS_YEAR: f32 = 30.0;
+/// How little time has to be left for the countdown to turn into a warning
+const WARNING_SECONDS: f32 = 30.0;
+const WARNING_COLOR: Color = Color::ORANGE_RED;
+
+/// Determines how long a level with the provided map size gets before the election is held
+pub const fn time_limit_for_map_size(map_size: usize) -> f32 {
+    BASE_TIME_LIMIT_SECONDS + (map_size * map_size) as f32 * TIME_LIMIT_SECONDS_PER_TILE
+}
+
+/// Counts down to the election at the end of the current level
+pub struct ElectionTimer {
+    /// How many seconds are left before the election is held
+    pub remaining: f32,
+}
+
+impl ElectionTimer {
+    pub fn expired(&self) -> bool {
+        self.remaining <= 0.0
+    }
+
+    /// Gets how many years in power winning the election is worth, including the bonus for the time left over
+    pub fn years_for_win(&self) -> u32 {
+        10 + self.bonus_years()
+    }
+
+    /// Gets how many extra years in power the time left over is worth
+    pub fn bonus_years(&self) -> u32 {
+        (self.remaining.max(0.0) / SECONDS_PER_BONUS_YEAR) as u32
+    }
+
+    /// Formats the time left as minutes and seconds
+    fn description(&self) -> String {
+        let seconds = self.remaining.max(0.0).ceil() as u32;
+        format!("Election in {}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[derive(Component)]
+pub struct ElectionTimerText;
+
+/// Spawns the countdown to the election
+pub fn spawn_election_timer(parent: &mut ChildBuilder, font: &Handle<Font>, timer: &ElectionTimer) {
+    parent
+        .spawn_bundle(TextBundle {
+            text: Text::with_section(
+                timer.description(),
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 20.0,
+                    color: Color::SEA_GREEN,
+                },
+                Default::default(),
+            ),
+            style: Style {
+                margin: Rect {
+                    bottom: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(ElectionTimerText);
+}
+
+/// Handles counting down to the election, and holding it on whatever plan exists once time runs out
+pub fn election_timer_system(
+    time: Res<Time>,
+    level: Res<Level>,
+    mut timer: ResMut<ElectionTimer>,
+    mut map: ResMut<Map>,
+    mut game_state: ResMut<State<GameState>>,
+    mut query: Query<&mut Text, With<ElectionTimerText>>,
+) {
+    if timer.expired() {
+        return;
+    }
+
+    timer.remaining -= time.delta_seconds();
+    for mut text in query.iter_mut() {
+        text.sections[0].value = timer.description();
+        text.sections[0].style.color = if timer.remaining <= WARNING_SECONDS {
+            WARNING_COLOR
+        } else {
+            Color::SEA_GREEN
+        };
+    }
+
+    if timer.expired() {
+        if level.empty_tile_rule == EmptyTileRule::AutoAssign {
+            map.fill_empty_tiles();
+        }
+        game_state.push(GameState::Results).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+
+/// A change to which district a single tile is in
+#[derive(Clone)]
+pub struct TileChange {
+    coords: Coordinates,
+    before: Option<u8>,
+    after: Option<u8>,
+}
+
+impl TileChange {
+    /// Puts the tile with the provided coordinates in the provided district without recording it anywhere.
+    /// Returns the change, if the tile wasn't already in that district.
+    pub fn apply(map: &mut Map, coords: &Coordinates, district_id: Option<u8>) -> Option<Self> {
+        let tile = map.get_mut(coords);
+        if tile.district_id == district_id {
+            return None;
+        }
+
+        let change = TileChange {
+            coords: coords.clone(),
+            before: tile.district_id,
+            after: district_id,
+        };
+        tile.district_id = district_id;
+        Some(change)
+    }
+
+    /// Determines whether the tile this change was made to is still the way the change left it
+    pub fn still_applied(&self, map: &Map) -> bool {
+        map.get(&self.coords).district_id == self.after
+    }
+}
+
+/// Keeps track of changes made to the map so they can be undone and redone
+#[derive(Default)]
+pub struct History {
+    undo_stack: Vec<Vec<TileChange>>,
+    redo_stack: Vec<Vec<TileChange>>,
+    /// The changes made so far in the action currently in progress, if there is one
+    current_action: Option<Vec<TileChange>>,
+}
+
+impl History {
+    /// Starts recording a new action. All changes until `end_action` is called will be undone together.
+    pub fn begin_action(&mut self) {
+        self.end_action();
+        self.current_action = Some(Vec::new());
+    }
+
+    /// Finishes recording the action in progress, if there is one
+    pub fn end_action(&mut self) {
+        if let Some(changes) = self.current_action.take() {
+            if !changes.is_empty() {
+                self.undo_stack.push(changes);
+                self.redo_stack.clear();
+            }
+        }
+    }
+
+    /// Puts the tile with the provided coordinates in the provided district, and records the change.
+    /// If no action is in progress, the change is recorded as an action of its own.
+    pub fn assign(&mut self, map: &mut Map, coords: &Coordinates, district_id: Option<u8>) {
+        let change = match TileChange::apply(map, coords, district_id) {
+            Some(change) => change,
+            None => return,
+        };
+
+        match &mut self.current_action {
+            Some(changes) => changes.push(change),
+            None => {
+                self.undo_stack.push(vec![change]);
+                self.redo_stack.clear();
+            }
+        }
+    }
+
+    /// Records changes that have already been made to the map as a single action of their own
+    pub fn record_action(&mut self, changes: Vec<TileChange>) {
+        self.end_action();
+        if !changes.is_empty() {
+            self.undo_stack.push(changes);
+            self.redo_stack.clear();
+        }
+    }
+
+    /// Reverts the most recent action, if there is one
+    pub fn undo(&mut self, map: &mut Map) {
+        self.end_action();
+        if let Some(changes) = self.undo_stack.pop() {
+            for change in changes.iter().rev() {
+                map.get_mut(&change.coords).district_id = change.before;
+            }
+            self.redo_stack.push(changes);
+        }
+    }
+
+    /// Re-applies the most recently undone action, if there is one
+    pub fn redo(&mut self, map: &mut Map) {
+        self.end_action();
+        if let Some(changes) = self.redo_stack.pop() {
+            for change in changes.iter() {
+                map.get_mut(&change.coords).district_id = change.after;
+            }
+            self.undo_stack.push(changes);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct UndoButton;
+
+#[derive(Component)]
+pub struct RedoButton;
+
+/// Spawns the undo and redo buttons
+pub fn spawn_history_buttons(parent: &mut ChildBuilder, font: &Handle<Font>) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_button(parent, font, "Undo", SMALL_BUTTON, UndoButton);
+            spawn_button(parent, font, "Redo", SMALL_BUTTON, RedoButton);
+        });
+}
+
+type InteractedUndoButtonTuple = (Changed<Interaction>, With<UndoButton>);
+type InteractedRedoButtonTuple = (Changed<Interaction>, With<RedoButton>);
+
+/// Handles undoing and redoing changes to the map
+pub fn undo_redo_system(
+    keyboard: Res<Input<KeyCode>>,
+    mut history: ResMut<History>,
+    mut map: ResMut<Map>,
+    undo_query: Query<&Interaction, InteractedUndoButtonTuple>,
+    redo_query: Query<&Interaction, InteractedRedoButtonTuple>,
+) {
+    let ctrl = keyboard.pressed(KeyCode::LControl) || keyboard.pressed(KeyCode::RControl);
+    let shift = keyboard.pressed(KeyCode::LShift) || keyboard.pressed(KeyCode::RShift);
+
+    let undo_pressed = (ctrl && !shift && keyboard.just_pressed(KeyCode::Z))
+        || undo_query
+            .iter()
+            .any(|interaction| *interaction == Interaction::Clicked);
+    let redo_pressed = (ctrl && keyboard.just_pressed(KeyCode::Y))
+        || (ctrl && shift && keyboard.just_pressed(KeyCode::Z))
+        || redo_query
+            .iter()
+            .any(|interaction| *interaction == Interaction::Clicked);
+
+    if undo_pressed {
+        history.undo(&mut map);
+    } else if redo_pressed {
+        history.redo(&mut map);
+    }
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+const CURSOR_COLOR: Color = Color::rgba(1.0, 1.0, 0.0, 0.5);
+/// How long a direction has to be held before the cursor starts moving repeatedly, in seconds
+const CURSOR_REPEAT_DELAY: f32 = 0.3;
+/// How long the cursor waits between moves while a direction is held, in seconds
+const CURSOR_REPEAT_INTERVAL: f32 = 0.08;
+
+/// A cursor that moves tile by tile, for playing with a keyboard or gamepad
+pub struct KeyboardCursor {
+    pub coords: Coordinates,
+    /// Whether the cursor has been used, and so should be shown
+    pub active: bool,
+    repeat_timer: Timer,
+}
+
+impl Default for KeyboardCursor {
+    fn default() -> Self {
+        KeyboardCursor {
+            coords: Coordinates { x: 0, y: 0 },
+            active: false,
+            repeat_timer: Timer::from_seconds(CURSOR_REPEAT_DELAY, false),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct KeyboardCursorHighlight;
+
+/// Handles moving the keyboard cursor, and painting and erasing with it
+pub fn keyboard_cursor_system(
+    time: Res<Time>,
+    actions: Res<Actions>,
+    selected_district: Res<SelectedDistrict>,
+    brush_size: Res<BrushSize>,
+    mut cursor: ResMut<KeyboardCursor>,
+    mut map: ResMut<Map>,
+    mut history: ResMut<History>,
+) {
+    if map.tiles.is_empty() {
+        return;
+    }
+
+    let directions = [
+        (Action::CursorUp, 0, -1),
+        (Action::CursorDown, 0, 1),
+        (Action::CursorLeft, -1, 0),
+        (Action::CursorRight, 1, 0),
+    ];
+    let mut movement = None;
+    if let Some((_, dx, dy)) = directions
+        .iter()
+        .find(|(action, _, _)| actions.just_pressed(*action))
+    {
+        cursor.repeat_timer = Timer::from_seconds(CURSOR_REPEAT_DELAY, false);
+        movement = Some((*dx, *dy));
+    } else if let Some((_, dx, dy)) = directions
+        .iter()
+        .find(|(action, _, _)| actions.pressed(*action))
+    {
+        cursor.repeat_timer.tick(time.delta());
+        if cursor.repeat_timer.finished() {
+            cursor.repeat_timer = Timer::from_seconds(CURSOR_REPEAT_INTERVAL, false);
+            movement = Some((*dx, *dy));
+        }
+    }
+
+    if let Some((dx, dy)) = movement {
+        let max_x = map.tiles[0].len() as isize - 1;
+        let max_y = map.tiles.len() as isize - 1;
+        cursor.coords = Coordinates {
+            x: (cursor.coords.x as isize + dx).clamp(0, max_x) as usize,
+            y: (cursor.coords.y as isize + dy).clamp(0, max_y) as usize,
+        };
+        cursor.active = true;
+    }
+
+    // everything painted from pressing a button to releasing it is undone together
+    if actions.just_pressed(Action::Paint) || actions.just_pressed(Action::Erase) {
+        history.begin_action();
+        cursor.active = true;
+    }
+
+    if actions.pressed(Action::Paint) {
+        let coords = cursor.coords.clone();
+        paint_brush(
+            &mut map,
+            &mut history,
+            &coords,
+            brush_size.0,
+            Some(selected_district.0),
+        );
+    } else if actions.pressed(Action::Erase) {
+        let coords = cursor.coords.clone();
+        paint_brush(&mut map, &mut history, &coords, brush_size.0, None);
+    } else if actions.just_released(Action::Paint) || actions.just_released(Action::Erase) {
+        history.end_action();
+    }
+}
+
+/// Handles showing where the keyboard cursor is
+pub fn keyboard_cursor_display_system(
+    mut commands: Commands,
+    cursor: Res<KeyboardCursor>,
+    layout: Res<MapLayout>,
+    mut highlight_query: Query<(&mut Transform, &mut Visibility), With<KeyboardCursorHighlight>>,
+) {
+    let on_map = cursor.coords.x < layout.num_columns && cursor.coords.y < layout.num_rows;
+    let tile_transform = on_map.then(|| Transform {
+        translation: layout.tile_position(&cursor.coords),
+        scale: layout.tile_size,
+        ..Default::default()
+    });
+
+    match highlight_query.get_single_mut() {
+        Ok((mut transform, mut visibility)) => {
+            visibility.is_visible = cursor.active && tile_transform.is_some();
+            if let Some(tile_transform) = tile_transform {
+                transform.translation = tile_transform.translation.truncate().extend(5.0);
+                transform.scale = tile_transform.scale;
+            }
+        }
+        Err(_) => {
+            if let Some(tile_transform) = tile_transform {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: CURSOR_COLOR,
+                            ..Default::default()
+                        },
+                        transform: Transform {
+                            translation: tile_transform.translation.truncate().extend(5.0),
+                            scale: tile_transform.scale,
+                            ..Default::default()
+                        },
+                        visibility: Visibility {
+                            is_visible: cursor.active,
+                        },
+                        ..Default::default()
+                    })
+                    .insert(GameComponent)
+                    .insert(KeyboardCursorHighlight);
+            }
+        }
+    }
+}
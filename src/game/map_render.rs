@@ -0,0 +1,337 @@
+use super::*;
+use bevy::{
+    render::{
+        mesh::Indices,
+        render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat},
+    },
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+/// How thick district borders are, relative to the size of a tile
+const BORDER_THICKNESS: f32 = 0.2;
+/// How many texels wide and tall each tile is in the fill texture, which leaves room for patterns
+const TEXELS_PER_TILE: usize = 4;
+/// How much darker than a tile's fill color its pattern is
+const PATTERN_BRIGHTNESS: f32 = 0.6;
+/// How long each border segment is, relative to the size of a tile, so neighboring segments overlap at the corners
+const BORDER_LENGTH: f32 = 1.2;
+
+/// The assets the map is drawn with. They're updated in place whenever the map changes, instead of having an entity per tile.
+pub struct MapGraphics {
+    /// Has a square of texels per tile, which is stretched over the whole map
+    fill_texture: Handle<Image>,
+    /// Has a quad for every segment of every district border
+    border_mesh: Handle<Mesh>,
+}
+
+/// The label showing the number of the district the tile with these coordinates is in
+#[derive(Component)]
+pub struct TileLabel(Coordinates);
+
+/// Handles spawning the map display whenever a new map is laid out
+pub fn map_graphics_setup_system(
+    mut commands: Commands,
+    layout: Res<MapLayout>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !layout.is_changed() || layout.num_rows == 0 || layout.num_columns == 0 {
+        return;
+    }
+
+    // tile fills
+    let fill_texture = images.add(Image::new_fill(
+        Extent3d {
+            width: (layout.num_columns * TEXELS_PER_TILE) as u32,
+            height: (layout.num_rows * TEXELS_PER_TILE) as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    ));
+    let cell_size = layout.tile_size.truncate() + Vec2::splat(layout.tile_spacing);
+    let top_right = layout.tile_position(&Coordinates {
+        x: layout.num_columns - 1,
+        y: 0,
+    });
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(
+                    cell_size * Vec2::new(layout.num_columns as f32, layout.num_rows as f32),
+                ),
+                ..Default::default()
+            },
+            texture: fill_texture.clone(),
+            transform: Transform::from_translation((layout.origin + top_right) / 2.0),
+            ..Default::default()
+        })
+        .insert(GameComponent);
+
+    // district borders
+    let border_mesh = meshes.add(build_border_mesh(&[], &layout));
+    commands
+        .spawn_bundle(MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(border_mesh.clone()),
+            material: materials.add(ColorMaterial::from(BORDER_COLOR)),
+            transform: Transform::from_xyz(0.0, 0.0, 3.0),
+            ..Default::default()
+        })
+        .insert(GameComponent);
+
+    // tile labels
+    let mono_font = asset_server.load(MONO_FONT);
+    for y in 0..layout.num_rows {
+        for x in 0..layout.num_columns {
+            let coords = Coordinates { x, y };
+            commands
+                .spawn_bundle(Text2dBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: mono_font.clone(),
+                            font_size: 25.0,
+                            color: Color::GREEN,
+                        },
+                        TextAlignment {
+                            vertical: VerticalAlign::Center,
+                            horizontal: HorizontalAlign::Center,
+                        },
+                    ),
+                    transform: Transform::from_translation(
+                        layout.tile_position(&coords).truncate().extend(2.0),
+                    ),
+                    ..Default::default()
+                })
+                .insert(GameComponent)
+                .insert(TileLabel(coords));
+        }
+    }
+
+    commands.insert_resource(MapGraphics {
+        fill_texture,
+        border_mesh,
+    });
+}
+
+/// Handles updating the map's colors based on tile assignments and the selected view
+#[allow(clippy::too_many_arguments)]
+pub fn map_update_system(
+    map: Res<Map>,
+    level: Res<Level>,
+    results: Res<DistrictResults>,
+    colors: Res<Colors>,
+    selected_view: Res<SelectedView>,
+    margin_overlay: Res<MarginOverlay>,
+    graphics: Option<Res<MapGraphics>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let graphics = match graphics {
+        Some(graphics) => graphics,
+        None => return,
+    };
+    if !map.is_changed()
+        && !colors.is_changed()
+        && !selected_view.is_changed()
+        && !margin_overlay.is_changed()
+        && !graphics.is_changed()
+    {
+        return;
+    }
+
+    let image = match images.get_mut(&graphics.fill_texture) {
+        Some(image) => image,
+        None => return,
+    };
+    let width = image.texture_descriptor.size.width as usize;
+    for row in map.tiles.iter() {
+        for tile in row {
+            let mut fill_color =
+                tile_fill_color(tile, selected_view.0, &results.0, &level, &colors);
+            if margin_overlay.0 {
+                fill_color = apply_margin_overlay(fill_color, tile, &results.0);
+            }
+            let pattern_color = pattern_color(fill_color);
+
+            for texel_y in 0..TEXELS_PER_TILE {
+                for texel_x in 0..TEXELS_PER_TILE {
+                    let color = if colors.patterns && in_pattern(&tile.content, texel_x, texel_y) {
+                        pattern_color
+                    } else {
+                        fill_color
+                    };
+                    let x = tile.coords.x * TEXELS_PER_TILE + texel_x;
+                    let y = tile.coords.y * TEXELS_PER_TILE + texel_y;
+                    let texel_idx = (y * width + x) * 4;
+                    if let Some(texel) = image.data.get_mut(texel_idx..texel_idx + 4) {
+                        for (channel, value) in texel.iter_mut().zip(color.as_rgba_f32()) {
+                            *channel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Gets the color of the pattern drawn over a tile with the provided fill color
+pub fn pattern_color(fill_color: Color) -> Color {
+    let [r, g, b, a] = fill_color.as_rgba_f32();
+    Color::rgba(
+        r * PATTERN_BRIGHTNESS,
+        g * PATTERN_BRIGHTNESS,
+        b * PATTERN_BRIGHTNESS,
+        a,
+    )
+}
+
+/// Determines whether the provided texel of a tile is part of the pattern for the tile's party.
+/// Good voters get a dot in the middle of the tile, and bad voters get a dot in each corner.
+fn in_pattern(content: &MapTileContent, texel_x: usize, texel_y: usize) -> bool {
+    let last = TEXELS_PER_TILE - 1;
+    let is_edge = |texel: usize| texel == 0 || texel == last;
+    match content {
+        MapTileContent::Good => !is_edge(texel_x) && !is_edge(texel_y),
+        MapTileContent::Bad => is_edge(texel_x) && is_edge(texel_y),
+        MapTileContent::Empty => false,
+    }
+}
+
+/// Handles rebuilding the district border geometry when tile assignments change
+pub fn border_system(
+    map: Res<Map>,
+    layout: Res<MapLayout>,
+    graphics: Option<Res<MapGraphics>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let graphics = match graphics {
+        Some(graphics) => graphics,
+        None => return,
+    };
+    if !map.is_changed() && !graphics.is_changed() {
+        return;
+    }
+
+    let mut segments = Vec::new();
+    for row in map.tiles.iter() {
+        for tile in row {
+            let coords = &tile.coords;
+            let neighbors = [
+                (map.get_up(coords), Vec2::new(0.0, 0.5)),
+                (map.get_down(coords), Vec2::new(0.0, -0.5)),
+                (map.get_left(coords), Vec2::new(-0.5, 0.0)),
+                (map.get_right(coords), Vec2::new(0.5, 0.0)),
+            ];
+            for (neighbor, offset) in neighbors {
+                let needs_border = match neighbor {
+                    Some(neighbor) => tile.district_id != neighbor.district_id,
+                    None => tile.district_id.is_some(),
+                };
+                if needs_border {
+                    segments.push((coords.clone(), offset));
+                }
+            }
+        }
+    }
+
+    if let Some(mesh) = meshes.get_mut(&graphics.border_mesh) {
+        *mesh = build_border_mesh(&segments, &layout);
+    }
+}
+
+/// Builds a mesh with a quad for each of the provided border segments.
+/// Each segment is on the edge of a tile, given by its offset from the tile's center relative to the tile's size.
+fn build_border_mesh(segments: &[(Coordinates, Vec2)], layout: &MapLayout) -> Mesh {
+    let tile_size = layout.tile_size.truncate();
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    for (coords, offset) in segments {
+        let center = layout.tile_position(coords).truncate() + *offset * tile_size;
+        let half_extents = if offset.x == 0.0 {
+            Vec2::new(BORDER_LENGTH, BORDER_THICKNESS) * tile_size / 2.0
+        } else {
+            Vec2::new(BORDER_THICKNESS, BORDER_LENGTH) * tile_size / 2.0
+        };
+
+        let first_idx = positions.len() as u32;
+        for corner in [
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ] {
+            let position = center + corner * half_extents;
+            positions.push([position.x, position.y, 0.0]);
+        }
+        indices.extend([0, 1, 2, 0, 2, 3].map(|idx| first_idx + idx));
+    }
+
+    // a mesh can't be empty, so use a single degenerate triangle when there are no borders
+    if positions.is_empty() {
+        positions = vec![[0.0, 0.0, 0.0]; 3];
+        indices = vec![0, 1, 2];
+    }
+
+    let num_vertices = positions.len();
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; num_vertices]);
+    mesh.set_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; num_vertices]);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh
+}
+
+/// Handles showing the number of the district each tile is in, colored by who's winning it
+pub fn tile_label_system(
+    map: Res<Map>,
+    results: Res<DistrictResults>,
+    colors: Res<Colors>,
+    graphics: Option<Res<MapGraphics>>,
+    mut query: Query<(&TileLabel, &mut Text)>,
+) {
+    let graphics_changed = graphics.map_or(false, |graphics| graphics.is_changed());
+    if !results.is_changed() && !colors.is_changed() && !graphics_changed {
+        return;
+    }
+
+    for (label, mut text) in query.iter_mut() {
+        let (value, color) = match map.get(&label.0).district_id {
+            Some(district_id) => {
+                let winner = results.0[district_id as usize].winner;
+                // add a symbol for who's winning, so the label's color isn't the only way to tell
+                let symbol = match (colors.patterns, winner) {
+                    (true, Some(DistrictWinner::Good)) => "+",
+                    (true, Some(DistrictWinner::Bad)) => "x",
+                    (true, Some(DistrictWinner::Tie)) => "=",
+                    _ => "",
+                };
+                (
+                    format!("{}{symbol}", district_id + 1),
+                    label_color(winner, &colors),
+                )
+            }
+            None => (String::new(), Color::NONE),
+        };
+
+        // only touch the labels that change, since changed text has to be laid out again
+        let section = &text.sections[0];
+        if section.value != value || section.style.color != color {
+            let section = &mut text.sections[0];
+            section.value = value;
+            section.style.color = color;
+        }
+    }
+}
+
+/// Gets the color a district's label is shown in, based on who's winning it
+pub fn label_color(winner: Option<DistrictWinner>, colors: &Colors) -> Color {
+    match winner {
+        Some(DistrictWinner::Good) => colors.good_regular,
+        Some(DistrictWinner::Bad) => colors.bad_regular,
+        Some(DistrictWinner::Tie) => colors.tie_label,
+        None => colors.undecided_label,
+    }
+}
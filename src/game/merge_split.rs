@@ -0,0 +1,202 @@
+use super::*;
+
+const SPLIT_LINE_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.8);
+const SPLIT_LINE_WIDTH: f32 = 3.0;
+const FREE_DISTRICT_TEXT_COLOR: Color = Color::GRAY;
+
+/// Keeps track of a cut line being dragged out with the split tool, in world coordinates
+#[derive(Default)]
+pub struct SplitLine {
+    pub start: Option<Vec2>,
+    pub end: Option<Vec2>,
+}
+
+#[derive(Component)]
+pub struct SplitLinePreview;
+
+/// Finds the lowest district ID that has no tiles in it, other than the provided one
+fn find_free_district(map: &Map, level: &Level, except: u8) -> Option<u8> {
+    (0..level.districts).find(|district_id| {
+        *district_id != except && map.get_tiles_in_district(*district_id).is_empty()
+    })
+}
+
+/// Moves every tile in the `from` district into the `into` district, which frees up the `from` district's ID.
+/// Does nothing unless the two districts are different and share a border.
+/// Returns whether the districts were merged.
+pub fn merge_districts(map: &mut Map, history: &mut History, into: u8, from: u8) -> bool {
+    if into == from {
+        return false;
+    }
+
+    let into_tiles = map.get_tiles_in_district(into);
+    let from_tiles = map.get_tiles_in_district(from);
+    let adjacent = from_tiles
+        .iter()
+        .any(|tile| !tile.find_adjacent_tiles(&into_tiles).is_empty());
+    if !adjacent {
+        return false;
+    }
+
+    let from_coords = from_tiles
+        .iter()
+        .map(|tile| tile.coords.clone())
+        .collect::<Vec<Coordinates>>();
+    history.begin_action();
+    for coords in from_coords {
+        history.assign(map, &coords, Some(into));
+    }
+    history.end_action();
+
+    true
+}
+
+/// Determines whether the line segment from `start` to `end` passes through the box with the provided center and half size
+fn segment_crosses_box(start: Vec2, end: Vec2, center: Vec2, half_size: Vec2) -> bool {
+    let direction = end - start;
+    let (mut t_min, mut t_max) = (0.0_f32, 1.0_f32);
+    for axis in 0..2 {
+        let (origin, delta) = (start[axis], direction[axis]);
+        let (low, high) = (
+            center[axis] - half_size[axis],
+            center[axis] + half_size[axis],
+        );
+        if delta.abs() < f32::EPSILON {
+            if origin < low || origin > high {
+                return false;
+            }
+            continue;
+        }
+
+        let (t_low, t_high) = ((low - origin) / delta, (high - origin) / delta);
+        t_min = t_min.max(t_low.min(t_high));
+        t_max = t_max.min(t_low.max(t_high));
+        if t_min > t_max {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Cuts the provided district along the line from `start` to `end`, and moves the tiles on the left side of the line into an unused district.
+/// Does nothing if every district ID is already in use, or if the line doesn't cut the district into two contiguous parts:
+/// it has to pass through the district's tiles and start and end outside of them.
+/// Returns the ID of the new district if the district was split.
+pub fn split_district(
+    map: &mut Map,
+    level: &Level,
+    history: &mut History,
+    layout: &MapLayout,
+    district_id: u8,
+    start: Vec2,
+    end: Vec2,
+) -> Option<u8> {
+    let direction = end - start;
+    if direction.length() < layout.tile_size.x.min(layout.tile_size.y) / 2.0 {
+        return None;
+    }
+    let in_district = |position: Vec2| {
+        layout.coords_at(position).map_or(false, |coords| {
+            map.get(&coords).district_id == Some(district_id)
+        })
+    };
+    if in_district(start) || in_district(end) {
+        return None;
+    }
+    let half_tile_size = layout.tile_size.truncate() / 2.0;
+    let crosses_district = map.get_tiles_in_district(district_id).iter().any(|tile| {
+        let center = layout.tile_position(&tile.coords).truncate();
+        segment_crosses_box(start, end, center, half_tile_size)
+    });
+    if !crosses_district {
+        return None;
+    }
+    let new_district_id = find_free_district(map, level, district_id)?;
+
+    let (left, right): (Vec<&MapTile>, Vec<&MapTile>) = map
+        .get_tiles_in_district(district_id)
+        .into_iter()
+        .partition(|tile| {
+            let position = layout.tile_position(&tile.coords).truncate();
+            direction.perp_dot(position - start) > 0.0
+        });
+    if left.is_empty() || right.is_empty() || !are_contiguous(&left) || !are_contiguous(&right) {
+        return None;
+    }
+
+    let left_coords = left
+        .iter()
+        .map(|tile| tile.coords.clone())
+        .collect::<Vec<Coordinates>>();
+    history.begin_action();
+    for coords in left_coords {
+        history.assign(map, &coords, Some(new_district_id));
+    }
+    history.end_action();
+
+    Some(new_district_id)
+}
+
+/// Handles showing the cut line being dragged out with the split tool
+pub fn split_line_preview_system(
+    mut commands: Commands,
+    split_line: Res<SplitLine>,
+    mut preview_query: Query<(Entity, &mut Transform), With<SplitLinePreview>>,
+) {
+    let (start, end) = match (split_line.start, split_line.end) {
+        (Some(start), Some(end)) => (start, end),
+        _ => {
+            for (entity, _) in preview_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        }
+    };
+
+    let direction = end - start;
+    let transform = Transform {
+        translation: ((start + end) / 2.0).extend(4.0),
+        rotation: Quat::from_rotation_z(direction.y.atan2(direction.x)),
+        scale: Vec3::new(direction.length(), SPLIT_LINE_WIDTH, 1.0),
+    };
+    if let Ok((_, mut preview_transform)) = preview_query.get_single_mut() {
+        *preview_transform = transform;
+    } else {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: SPLIT_LINE_COLOR,
+                    ..Default::default()
+                },
+                transform,
+                ..Default::default()
+            })
+            .insert(GameComponent)
+            .insert(SplitLinePreview);
+    }
+}
+
+/// Handles greying out the selector buttons of districts that have no tiles in them
+pub fn free_district_text_system(
+    map: Res<Map>,
+    button_query: Query<(&DistrictSelector, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !map.is_changed() {
+        return;
+    }
+
+    for (district_selector, children) in button_query.iter() {
+        let color = if map.get_tiles_in_district(district_selector.0).is_empty() {
+            FREE_DISTRICT_TEXT_COLOR
+        } else {
+            Color::SEA_GREEN
+        };
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use super::*;
+
+const PAUSE_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.85);
+const PAUSE_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(300.0),
+        height: Val::Px(50.0),
+    },
+    margin: 10.0,
+    font_size: 25.0,
+};
+
+#[derive(Component)]
+pub struct PauseComponent;
+
+#[derive(Component, Clone, Copy)]
+pub enum PauseButton {
+    Resume,
+    /// Starts the level over on the same map, with no districts drawn
+    Restart,
+    /// Starts the level over on a new map
+    NewMap,
+    QuitToMenu,
+}
+
+/// Handles pausing and unpausing the game with the escape key
+pub fn pause_toggle_system(
+    keyboard: Res<Input<KeyCode>>,
+    mut game_state: ResMut<State<GameState>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    // another system may have already queued a transition this frame, in which case the key press is dropped
+    let _ = match game_state.current() {
+        GameState::Game => game_state.push(GameState::Paused),
+        GameState::Paused => game_state.pop(),
+        _ => Ok(()),
+    };
+}
+
+/// Sets up the pause menu, on top of the game screen
+pub fn pause_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    colors: Res<Colors>,
+    audio_settings: Res<AudioSettings>,
+) {
+    let font = asset_server.load(MAIN_FONT);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(PauseComponent)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        padding: Rect::all(Val::Px(20.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        flex_direction: FlexDirection::ColumnReverse,
+                        ..Default::default()
+                    },
+                    color: PAUSE_BACKGROUND_COLOR.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            "Paused",
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 40.0,
+                                color: Color::WHITE,
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    });
+
+                    spawn_button(parent, &font, "Resume", PAUSE_BUTTON, PauseButton::Resume);
+                    spawn_button(
+                        parent,
+                        &font,
+                        "Restart level",
+                        PAUSE_BUTTON,
+                        PauseButton::Restart,
+                    );
+                    spawn_button(parent, &font, "New map", PAUSE_BUTTON, PauseButton::NewMap);
+
+                    // settings, two to a row
+                    for settings in Setting::ALL.chunks(2) {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Row,
+                                    ..Default::default()
+                                },
+                                color: UiColor(Color::NONE),
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                for setting in settings {
+                                    spawn_setting_button(
+                                        parent,
+                                        &font,
+                                        *setting,
+                                        &colors,
+                                        &audio_settings,
+                                        (),
+                                    );
+                                }
+                            });
+                    }
+
+                    spawn_button(
+                        parent,
+                        &font,
+                        "Quit to menu",
+                        PAUSE_BUTTON,
+                        PauseButton::QuitToMenu,
+                    );
+                    spawn_button(parent, &font, "Exit game", PAUSE_BUTTON, ExitButton);
+                });
+        });
+}
+
+/// Handles interactions with the pause menu buttons
+#[allow(clippy::too_many_arguments)]
+pub fn pause_button_system(
+    mut game_state: ResMut<State<GameState>>,
+    mut map: ResMut<Map>,
+    mut level: ResMut<Level>,
+    mut history: ResMut<History>,
+    mut timer: ResMut<ElectionTimer>,
+    mut solved: ResMut<Solved>,
+    mut selected_district: ResMut<SelectedDistrict>,
+    score: Res<Score>,
+    brush_size: Res<BrushSize>,
+    asset_server: Res<AssetServer>,
+    colors: Res<Colors>,
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    to_despawn_query: Query<Entity, With<GameComponent>>,
+) {
+    let pressed = interaction_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Clicked)
+        .map(|(_, button)| *button);
+
+    match pressed {
+        Some(PauseButton::Resume) => game_state.pop().unwrap(),
+        Some(PauseButton::Restart) => {
+            for tile in map.tiles.iter_mut().flatten() {
+                tile.district_id = None;
+            }
+            *history = History::default();
+            timer.remaining = level.time_limit;
+            selected_district.0 = 0;
+            // anything left over from before the restart would act on the cleared map
+            commands.insert_resource(AutoBalance::default());
+            commands.insert_resource(SplitLine::default());
+            commands.insert_resource(RectangleDrag::default());
+            game_state.pop().unwrap();
+        }
+        Some(PauseButton::NewMap) => {
+            level.seed = rand::thread_rng().gen();
+            solved.0 = false;
+            selected_district.0 = 0;
+            despawn_components(to_despawn_query, &mut commands);
+            set_up_game(
+                &mut commands,
+                &asset_server,
+                &colors,
+                &mut level,
+                &score,
+                &brush_size,
+            );
+            game_state.pop().unwrap();
+        }
+        Some(PauseButton::QuitToMenu) => game_state.replace(GameState::Menu).unwrap(),
+        None => (),
+    }
+}
@@ -0,0 +1,325 @@
+use super::*;
+
+/// How long it takes to count the votes in each district
+const SECONDS_PER_DISTRICT: f32 = 1.0;
+/// How long to wait before the confirm action skips the count, so the press that confirmed the plan doesn't also skip it
+const SKIP_DELAY_SECONDS: f32 = 0.25;
+const RESULTS_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.85);
+const CONTINUE_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(120.0),
+        height: Val::Px(50.0),
+    },
+    margin: 10.0,
+    font_size: 20.0,
+};
+
+#[derive(Component)]
+pub struct ResultsComponent;
+
+#[derive(Component)]
+pub struct TallyText;
+
+#[derive(Component)]
+pub struct SeatCounterText;
+
+#[derive(Component)]
+pub struct ResultsBanner;
+
+#[derive(Component)]
+pub struct ContinueButton;
+
+#[derive(Component)]
+pub struct ContinueButtonParent;
+
+/// Keeps track of how far along the election night count is
+pub struct ResultsReveal {
+    results: Vec<DistrictResult>,
+    /// How many seconds the count has been going for
+    elapsed: f32,
+}
+
+impl ResultsReveal {
+    /// Gets how many districts have been fully counted
+    fn num_counted(&self) -> usize {
+        ((self.elapsed / SECONDS_PER_DISTRICT) as usize).min(self.results.len())
+    }
+
+    fn finished(&self) -> bool {
+        self.num_counted() == self.results.len()
+    }
+
+    /// Determines whether the good party won a majority of the seats, once every district has been counted
+    pub fn majority_won(&self, level: &Level) -> bool {
+        self.finished() && majority_of_seats(&self.results, level)
+    }
+
+    /// Gets the results of every district, whether they've been counted yet or not
+    pub fn results(&self) -> &[DistrictResult] {
+        &self.results
+    }
+
+    /// Gets how many seats each party has won in the districts counted so far, good party first
+    pub fn seats(&self, level: &Level) -> (usize, usize) {
+        count_seats(&self.results[..self.num_counted()], level)
+    }
+}
+
+/// Gets how many seats each party wins in the provided districts, good party first.
+/// Districts that break the level's rules, like ones left unfinished when time ran out, go to the bad party.
+pub fn count_seats(results: &[DistrictResult], level: &Level) -> (usize, usize) {
+    let good_seats = results
+        .iter()
+        .filter(|result| result.elected_party(level) == DistrictWinner::Good)
+        .count();
+    (good_seats, results.len() - good_seats)
+}
+
+/// Determines whether the good party wins a majority of the seats with the provided district results
+pub fn majority_of_seats(results: &[DistrictResult], level: &Level) -> bool {
+    count_seats(results, level).0 > level.districts as usize / 2
+}
+
+/// Sets up the election night results screen, on top of the game screen
+pub fn results_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    results: Res<DistrictResults>,
+    timer: Res<ElectionTimer>,
+) {
+    commands.insert_resource(ResultsReveal {
+        results: results.0.clone(),
+        elapsed: 0.0,
+    });
+
+    let font = asset_server.load(MAIN_FONT);
+    let mono_font = asset_server.load(MONO_FONT);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(ResultsComponent)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        padding: Rect::all(Val::Px(20.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        flex_direction: FlexDirection::ColumnReverse,
+                        ..Default::default()
+                    },
+                    color: RESULTS_BACKGROUND_COLOR.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            if timer.expired() {
+                                "Time's up! Election night"
+                            } else {
+                                "Election night"
+                            },
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 40.0,
+                                color: Color::WHITE,
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    });
+
+                    spawn_results_text(parent, &mono_font, TallyText);
+                    spawn_results_text(parent, &mono_font, SeatCounterText);
+
+                    parent
+                        .spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                "",
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 40.0,
+                                    color: Color::GOLD,
+                                },
+                                Default::default(),
+                            ),
+                            ..Default::default()
+                        })
+                        .insert(ResultsBanner);
+
+                    // the button is hidden until the count is finished
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                display: Display::None,
+                                ..Default::default()
+                            },
+                            color: UiColor(Color::NONE),
+                            ..Default::default()
+                        })
+                        .insert(ContinueButtonParent)
+                        .with_children(|parent| {
+                            spawn_button(
+                                parent,
+                                &font,
+                                "Continue",
+                                CONTINUE_BUTTON,
+                                ContinueButton,
+                            );
+                        });
+                });
+        });
+}
+
+fn spawn_results_text(parent: &mut ChildBuilder, font: &Handle<Font>, component: impl Component) {
+    parent
+        .spawn_bundle(TextBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 20.0,
+                    color: Color::SEA_GREEN,
+                },
+                Default::default(),
+            ),
+            style: Style {
+                margin: Rect::all(Val::Px(10.0)),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(component);
+}
+
+type TallyTextTuple = (
+    With<TallyText>,
+    Without<SeatCounterText>,
+    Without<ResultsBanner>,
+);
+type SeatCounterTextTuple = (With<SeatCounterText>, Without<ResultsBanner>);
+
+/// Handles counting the votes district by district
+#[allow(clippy::too_many_arguments)]
+pub fn results_reveal_system(
+    time: Res<Time>,
+    actions: Res<Actions>,
+    level: Res<Level>,
+    colors: Res<Colors>,
+    timer: Res<ElectionTimer>,
+    mut reveal: ResMut<ResultsReveal>,
+    mut tally_query: Query<&mut Text, TallyTextTuple>,
+    mut seat_counter_query: Query<&mut Text, SeatCounterTextTuple>,
+    mut banner_query: Query<&mut Text, With<ResultsBanner>>,
+    mut continue_button_query: Query<&mut Style, With<ContinueButtonParent>>,
+) {
+    if reveal.finished() {
+        return;
+    }
+
+    reveal.elapsed += time.delta_seconds();
+    if reveal.elapsed > SKIP_DELAY_SECONDS && actions.just_pressed(Action::Confirm) {
+        reveal.elapsed = SECONDS_PER_DISTRICT * reveal.results.len() as f32;
+    }
+
+    // show the final tallies of the counted districts, and the running tally of the one being counted
+    let num_counted = reveal.num_counted();
+    let mut tally = String::new();
+    for (district_id, result) in reveal.results.iter().enumerate() {
+        let counted_fraction = if district_id < num_counted {
+            1.0
+        } else if district_id == num_counted {
+            (reveal.elapsed % SECONDS_PER_DISTRICT) / SECONDS_PER_DISTRICT
+        } else {
+            break;
+        };
+        let count = |votes: usize| (votes as f32 * counted_fraction).round() as usize;
+        tally.push_str(&format!(
+            "District {:>2}: {} {:>3} - {} {:>3}",
+            district_id + 1,
+            colors.good_color_name,
+            count(result.good_votes),
+            colors.bad_color_name,
+            count(result.bad_votes),
+        ));
+        if district_id < num_counted {
+            let outcome = match (result.validity(&level), result.winner, result.seat_winner()) {
+                (DistrictValidity::TooSmall, _, _) => "lost, too small",
+                (DistrictValidity::TooBig, _, _) => "lost, too big",
+                (DistrictValidity::NonContiguous, _, _) => "lost, not contiguous",
+                (_, Some(DistrictWinner::Tie), Some(DistrictWinner::Good)) => "won on a tie",
+                (_, Some(DistrictWinner::Tie), _) => "lost on a tie",
+                (_, _, Some(DistrictWinner::Good)) => "won",
+                _ => "lost",
+            };
+            tally.push_str(&format!(" [{outcome}]"));
+        }
+        tally.push('\n');
+    }
+    for mut text in tally_query.iter_mut() {
+        text.sections[0].value = tally.clone();
+    }
+
+    let (good_seats, bad_seats) = reveal.seats(&level);
+    for mut text in seat_counter_query.iter_mut() {
+        text.sections[0].value = format!(
+            "Seats: {} {good_seats}, {} {bad_seats} ({} needed for a majority)",
+            colors.good_color_name,
+            colors.bad_color_name,
+            level.districts / 2 + 1,
+        );
+    }
+
+    if reveal.finished() {
+        for mut text in banner_query.iter_mut() {
+            text.sections[0].value = if reveal.majority_won(&level) && timer.bonus_years() > 0 {
+                format!(
+                    "Majority won! +{} bonus years for finishing early",
+                    timer.bonus_years()
+                )
+            } else if reveal.majority_won(&level) {
+                "Majority won!".to_string()
+            } else {
+                "Majority lost. You've been voted out.".to_string()
+            };
+        }
+        for mut style in continue_button_query.iter_mut() {
+            style.display = Display::Flex;
+        }
+    }
+}
+
+type InteractedContinueButtonTuple = (Changed<Interaction>, With<ContinueButton>);
+
+/// Handles moving on from the results once they've all been counted, to the next level or to the game over screen
+pub fn continue_button_system(
+    mut game_state: ResMut<State<GameState>>,
+    actions: Res<Actions>,
+    level: Res<Level>,
+    reveal: Res<ResultsReveal>,
+    interaction_query: Query<&Interaction, InteractedContinueButtonTuple>,
+) {
+    if !reveal.finished() || reveal.is_changed() {
+        return;
+    }
+
+    let clicked = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked || actions.just_pressed(Action::Confirm) {
+        if reveal.majority_won(&level) {
+            game_state.pop().unwrap();
+        } else {
+            game_state.replace(GameState::GameOver).unwrap();
+        }
+    }
+}
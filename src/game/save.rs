@@ -0,0 +1,165 @@
+use bevy::window::WindowCloseRequested;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// The name the run is stored under
+const SAVE_NAME: &str = "save";
+/// How often the run is saved while it's being changed
+const AUTOSAVE_INTERVAL_SECONDS: f64 = 1.0;
+
+/// Everything needed to pick a run back up where it was left off
+#[derive(Serialize, Deserialize)]
+pub struct SavedRun {
+    pub party: Party,
+    pub score: u32,
+    pub stats: RunStats,
+    pub level: Level,
+    pub map: Map,
+    pub selected_district: u8,
+    /// How many seconds were left before the election
+    pub time_remaining: f32,
+}
+
+/// Keeps track of when the run needs to be saved
+#[derive(Default)]
+pub struct Autosave {
+    /// Whether the run has changed since it was last saved
+    dirty: bool,
+    /// When the run was last saved, in seconds since startup
+    last_saved: f64,
+}
+
+/// Determines whether there's a saved run that can be continued
+pub fn saved_run_exists() -> bool {
+    is_stored(SAVE_NAME)
+}
+
+/// Loads the saved run, if there is one and it can be read
+pub fn load_run() -> Option<SavedRun> {
+    load_stored(SAVE_NAME)
+}
+
+/// Handles saving the run shortly after it changes, or right away if the window is being closed.
+/// The time left is saved along with the changes, rather than every time the timer ticks.
+#[allow(clippy::too_many_arguments)]
+pub fn autosave_system(
+    time: Res<Time>,
+    mut close_events: EventReader<WindowCloseRequested>,
+    mut autosave: ResMut<Autosave>,
+    map: Res<Map>,
+    level: Res<Level>,
+    score: Res<Score>,
+    stats: Res<RunStats>,
+    selected_district: Res<SelectedDistrict>,
+    timer: Res<ElectionTimer>,
+    colors: Res<Colors>,
+) {
+    if map.is_changed()
+        || level.is_changed()
+        || score.is_changed()
+        || selected_district.is_changed()
+    {
+        autosave.dirty = true;
+    }
+
+    let closing = close_events.iter().count() > 0;
+    let now = time.seconds_since_startup();
+    if !closing && (!autosave.dirty || now - autosave.last_saved < AUTOSAVE_INTERVAL_SECONDS) {
+        return;
+    }
+
+    save_run(
+        &map,
+        &level,
+        &score,
+        &stats,
+        &selected_district,
+        &timer,
+        &colors,
+    );
+    autosave.dirty = false;
+    autosave.last_saved = now;
+}
+
+/// Handles saving the run right away, for when the player might be about to leave
+#[allow(clippy::too_many_arguments)]
+pub fn save_run_system(
+    mut autosave: ResMut<Autosave>,
+    map: Res<Map>,
+    level: Res<Level>,
+    score: Res<Score>,
+    stats: Res<RunStats>,
+    selected_district: Res<SelectedDistrict>,
+    timer: Res<ElectionTimer>,
+    colors: Res<Colors>,
+) {
+    save_run(
+        &map,
+        &level,
+        &score,
+        &stats,
+        &selected_district,
+        &timer,
+        &colors,
+    );
+    autosave.dirty = false;
+}
+
+/// Handles saving the outcome of the election as soon as it's held, so leaving during the count can't undo it.
+/// A won election is saved as the start of the next level, and a lost one ends the run, so the saved run is deleted.
+pub fn save_election_outcome_system(
+    mut autosave: ResMut<Autosave>,
+    level: Res<Level>,
+    results: Res<DistrictResults>,
+    score: Res<Score>,
+    stats: Res<RunStats>,
+    timer: Res<ElectionTimer>,
+    colors: Res<Colors>,
+) {
+    autosave.dirty = false;
+    if !majority_of_seats(&results.0, &level) {
+        delete_stored(SAVE_NAME);
+        return;
+    }
+
+    let mut stats = stats.clone();
+    stats.record_election(&results.0, &level);
+    let next_level = generate_next_level(&level, &results.0);
+    let saved_run = SavedRun {
+        party: colors.party,
+        score: score.0 + timer.years_for_win(),
+        stats,
+        map: Map::generate(&next_level),
+        selected_district: 0,
+        time_remaining: next_level.time_limit,
+        level: next_level,
+    };
+    store(SAVE_NAME, &saved_run);
+}
+
+/// Handles deleting the saved run once it's over, so it can't be continued
+pub fn delete_saved_run_system() {
+    delete_stored(SAVE_NAME);
+}
+
+fn save_run(
+    map: &Map,
+    level: &Level,
+    score: &Score,
+    stats: &RunStats,
+    selected_district: &SelectedDistrict,
+    timer: &ElectionTimer,
+    colors: &Colors,
+) {
+    let saved_run = SavedRun {
+        party: colors.party,
+        score: score.0,
+        stats: stats.clone(),
+        level: level.clone(),
+        map: map.clone(),
+        selected_district: selected_district.0,
+        time_remaining: timer.remaining,
+    };
+    store(SAVE_NAME, &saved_run);
+}
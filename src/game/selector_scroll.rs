@@ -0,0 +1,80 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+
+use super::*;
+
+/// How wide the district selector column is, in pixels at the normal UI scale
+pub const SELECTOR_COLUMN_WIDTH: f32 = 360.0;
+/// How far one notch of the mouse wheel scrolls the district selector column, in pixels
+const SCROLL_SPEED: f32 = 40.0;
+
+#[derive(Component)]
+pub struct SelectorColumn;
+
+/// The contents of the district selector column, which move up and down as it scrolls
+#[derive(Component)]
+pub struct SelectorList;
+
+/// How far the district selector column is scrolled down
+#[derive(Default)]
+pub struct SelectorScroll {
+    offset: f32,
+    /// Whether the cursor is over the column, so the mouse wheel scrolls it instead of zooming the map
+    pub hovered: bool,
+}
+
+/// Handles scrolling the district selector column with the mouse wheel or by dragging with a finger
+pub fn selector_scroll_system(
+    windows: Res<Windows>,
+    touches: Res<Touches>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut scroll: ResMut<SelectorScroll>,
+    column_query: Query<(&Node, &GlobalTransform), With<SelectorColumn>>,
+    mut list_query: Query<(&Node, &mut Style), With<SelectorList>>,
+) {
+    let (column_node, column_transform) = match column_query.get_single() {
+        Ok(column) => column,
+        Err(_) => return,
+    };
+    let (list_node, mut list_style) = match list_query.get_single_mut() {
+        Ok(list) => list,
+        Err(_) => return,
+    };
+
+    // the column's transform is at its center, in window coordinates
+    let column_center = column_transform.translation.truncate();
+    let is_over_column = |position: Vec2| {
+        let distance = (position - column_center).abs();
+        distance.x <= column_node.size.x / 2.0 && distance.y <= column_node.size.y / 2.0
+    };
+    let cursor_position = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position());
+    scroll.hovered = cursor_position.map_or(false, is_over_column);
+
+    let mut movement = 0.0;
+    for event in wheel_events.iter() {
+        if scroll.hovered {
+            movement -= match event.unit {
+                MouseScrollUnit::Line => event.y * SCROLL_SPEED,
+                MouseScrollUnit::Pixel => event.y,
+            };
+        }
+    }
+
+    // touch positions are measured from the top of the window
+    let window_height = windows.get_primary().map_or(0.0, |window| window.height());
+    let mut active_touches = touches.iter();
+    if let (Some(touch), None) = (active_touches.next(), active_touches.next()) {
+        let position = Vec2::new(touch.position().x, window_height - touch.position().y);
+        if is_over_column(position) {
+            movement -= touch.delta().y;
+        }
+    }
+
+    let max_offset = (list_node.size.y - column_node.size.y).max(0.0);
+    let offset = (scroll.offset + movement).clamp(0.0, max_offset);
+    if offset != scroll.offset || list_style.position.top != Val::Px(-offset) {
+        scroll.offset = offset;
+        list_style.position.top = Val::Px(-offset);
+    }
+}
@@ -0,0 +1,80 @@
+use super::*;
+
+/// What the map looked like the last time it was checked for sound events
+#[derive(Default)]
+pub struct SoundSnapshot {
+    assignments: Vec<Option<u8>>,
+    validities: Vec<DistrictValidity>,
+    solved: bool,
+}
+
+/// Handles sending sound events for changes to the map: tiles being painted or erased, districts becoming valid or invalid, and the level being solved
+pub fn map_sound_event_system(
+    map: Res<Map>,
+    level: Res<Level>,
+    results: Res<DistrictResults>,
+    solved: Res<Solved>,
+    mut snapshot: ResMut<SoundSnapshot>,
+    mut sound_events: EventWriter<SoundEvent>,
+) {
+    if !map.is_changed() && !solved.is_changed() {
+        return;
+    }
+
+    let assignments = map
+        .tiles
+        .iter()
+        .flatten()
+        .map(|tile| tile.district_id)
+        .collect::<Vec<Option<u8>>>();
+    let validities = results
+        .0
+        .iter()
+        .map(|result| result.validity(&level))
+        .collect::<Vec<DistrictValidity>>();
+
+    // a new map was set up, so there's nothing to compare against
+    let same_map = assignments.len() == snapshot.assignments.len()
+        && validities.len() == snapshot.validities.len();
+    if same_map {
+        let (mut painted, mut erased) = (false, false);
+        for (before, after) in snapshot.assignments.iter().zip(&assignments) {
+            if before != after {
+                if after.is_some() {
+                    painted = true;
+                } else {
+                    erased = true;
+                }
+            }
+        }
+        if painted {
+            sound_events.send(SoundEvent::Paint);
+        }
+        if erased {
+            sound_events.send(SoundEvent::Erase);
+        }
+
+        for (before, after) in snapshot.validities.iter().zip(&validities) {
+            if *after == DistrictValidity::Valid && *before != DistrictValidity::Valid {
+                sound_events.send(SoundEvent::DistrictValid);
+            } else if *before == DistrictValidity::Valid && *after != DistrictValidity::Valid {
+                sound_events.send(SoundEvent::DistrictInvalid);
+            }
+        }
+
+        if solved.0 && !snapshot.solved {
+            sound_events.send(SoundEvent::Solved);
+        }
+    }
+
+    *snapshot = SoundSnapshot {
+        assignments,
+        validities,
+        solved: solved.0,
+    };
+}
+
+/// Sends a sound event when the player confirms their plan
+pub fn confirm_sound_event_system(mut sound_events: EventWriter<SoundEvent>) {
+    sound_events.send(SoundEvent::Confirm);
+}
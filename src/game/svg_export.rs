@@ -0,0 +1,364 @@
+use std::{collections::HashMap, fmt::Write as _, fs};
+
+use super::*;
+
+/// How big each tile is in the exported image
+const SVG_TILE_SIZE: usize = 24;
+/// The space around the map and legend in the exported image
+const SVG_MARGIN: usize = 20;
+/// How wide the legend next to the map is
+const SVG_LEGEND_WIDTH: usize = 320;
+/// The height of each line of the legend
+const SVG_LINE_HEIGHT: usize = 22;
+const SVG_BACKGROUND_COLOR: Color = Color::WHITE;
+const SVG_TEXT_COLOR: Color = Color::BLACK;
+
+#[derive(Component)]
+pub struct ExportButton;
+
+#[derive(Component)]
+pub struct ExportStatusText;
+
+/// Spawns the button that exports the district plan, along with the text saying where it went
+pub fn spawn_export_button(parent: &mut ChildBuilder, font: &Handle<Font>) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_button(parent, font, "SVG", SMALL_BUTTON, ExportButton);
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 16.0,
+                            color: Color::SEA_GREEN,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(ExportStatusText);
+        });
+}
+
+/// Handles exporting the district plan to an SVG file when the export button or shortcut is pressed
+#[allow(clippy::too_many_arguments)]
+pub fn export_button_system(
+    map: Res<Map>,
+    level: Res<Level>,
+    results: Res<DistrictResults>,
+    colors: Res<Colors>,
+    selected_view: Res<SelectedView>,
+    margin_overlay: Res<MarginOverlay>,
+    actions: Res<Actions>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ExportButton>)>,
+    mut text_query: Query<&mut Text, With<ExportStatusText>>,
+) {
+    let clicked = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if !clicked && !actions.just_pressed(Action::ExportPlan) {
+        return;
+    }
+
+    let svg = plan_svg(
+        &map,
+        &level,
+        &results.0,
+        &colors,
+        selected_view.0,
+        margin_overlay.0,
+    );
+    let file_name = format!("district-plan-{:016x}.svg", level.seed);
+    let status = match fs::write(&file_name, svg) {
+        Ok(()) => format!("Saved {}", file_name),
+        Err(e) => {
+            warn!("Unable to export district plan to {}: {}", file_name, e);
+            "Export failed".to_string()
+        }
+    };
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = status.clone();
+    }
+}
+
+/// Draws the provided map as an SVG image, colored the way it's shown in the provided view, with a legend of each district's result
+fn plan_svg(
+    map: &Map,
+    level: &Level,
+    results: &[DistrictResult],
+    colors: &Colors,
+    view: ViewMode,
+    margin_overlay: bool,
+) -> String {
+    let num_rows = map.tiles.len();
+    let num_columns = map.tiles.first().map_or(0, |row| row.len());
+    let legend_lines = level.districts as usize + 3;
+    let width = SVG_MARGIN * 3 + num_columns * SVG_TILE_SIZE + SVG_LEGEND_WIDTH;
+    let height = SVG_MARGIN * 2 + (num_rows * SVG_TILE_SIZE).max(legend_lines * SVG_LINE_HEIGHT);
+
+    let mut svg = String::new();
+    // writing to a string can't fail, so the results of `write!` are ignored
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{width}" height="{height}" fill="{}"/>"#,
+        svg_color(SVG_BACKGROUND_COLOR)
+    );
+
+    // tile fills
+    let _ = writeln!(
+        svg,
+        r#"<g transform="translate({SVG_MARGIN} {SVG_MARGIN})" shape-rendering="crispEdges">"#
+    );
+    for tile in map.tiles.iter().flatten() {
+        let mut fill_color = tile_fill_color(tile, view, results, level, colors);
+        if margin_overlay {
+            fill_color = apply_margin_overlay(fill_color, tile, results);
+        }
+        let (x, y) = (tile.coords.x * SVG_TILE_SIZE, tile.coords.y * SVG_TILE_SIZE);
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{x}" y="{y}" width="{SVG_TILE_SIZE}" height="{SVG_TILE_SIZE}" fill="{}"/>"#,
+            svg_color(fill_color)
+        );
+        if colors.patterns {
+            write_tile_pattern(&mut svg, tile, x, y, pattern_color(fill_color));
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    // district outlines
+    let _ = writeln!(
+        svg,
+        r#"<g transform="translate({SVG_MARGIN} {SVG_MARGIN})" fill="none" stroke="{}" stroke-width="3" stroke-linejoin="round">"#,
+        svg_color(BORDER_COLOR)
+    );
+    for district_id in 0..level.districts {
+        let outline = district_outline(map, district_id);
+        if !outline.is_empty() {
+            let _ = writeln!(svg, r#"<path d="{outline}"/>"#);
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    // district numbers
+    let _ = writeln!(
+        svg,
+        r#"<g transform="translate({SVG_MARGIN} {SVG_MARGIN})" font-family="monospace" font-size="14" font-weight="bold" text-anchor="middle" dominant-baseline="central" stroke="{}" stroke-width="3" paint-order="stroke">"#,
+        svg_color(SVG_BACKGROUND_COLOR)
+    );
+    for (district_id, result) in results.iter().enumerate() {
+        if let Some(coords) = map.district_label_coords(district_id as u8) {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" fill="{}">{}</text>"#,
+                coords.x * SVG_TILE_SIZE + SVG_TILE_SIZE / 2,
+                coords.y * SVG_TILE_SIZE + SVG_TILE_SIZE / 2,
+                svg_color(label_color(result.winner, colors)),
+                district_id + 1
+            );
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    // legend
+    let seats_for = |party| {
+        results
+            .iter()
+            .filter(|result| result.validity(level) == DistrictValidity::Valid)
+            .filter(|result| result.seat_winner() == Some(party))
+            .count()
+    };
+    let _ = writeln!(
+        svg,
+        r#"<g transform="translate({} {SVG_MARGIN})" font-size="14" fill="{}">"#,
+        SVG_MARGIN * 2 + num_columns * SVG_TILE_SIZE,
+        svg_color(SVG_TEXT_COLOR)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text y="{}" font-weight="bold">{} districts, {} to {} voters each</text>"#,
+        SVG_LINE_HEIGHT - 6,
+        level.districts,
+        level.min_district_size,
+        level.max_district_size
+    );
+    for (district_id, result) in results.iter().enumerate() {
+        let y = (district_id + 1) * SVG_LINE_HEIGHT;
+        let swatch_color = match result.seat_winner() {
+            Some(DistrictWinner::Good) => colors.good_regular,
+            Some(DistrictWinner::Bad) => colors.bad_regular,
+            _ => colors.undecided_label,
+        };
+        let _ = writeln!(
+            svg,
+            r#"<rect y="{}" width="14" height="14" fill="{}" stroke="{}"/>"#,
+            y + 3,
+            svg_color(swatch_color),
+            svg_color(SVG_TEXT_COLOR)
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="22" y="{}">{}</text>"#,
+            y + SVG_LINE_HEIGHT - 6,
+            district_legend_text(district_id, result, level, colors)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<text y="{}" font-weight="bold">Seats: {} {}, {} {} ({} needed)</text>"#,
+        (level.districts as usize + 2) * SVG_LINE_HEIGHT - 6,
+        colors.good_color_name,
+        seats_for(DistrictWinner::Good),
+        colors.bad_color_name,
+        seats_for(DistrictWinner::Bad),
+        level.districts / 2 + 1
+    );
+    let _ = writeln!(svg, "</g>");
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Describes the provided district's votes and outcome for the legend
+fn district_legend_text(
+    district_id: usize,
+    result: &DistrictResult,
+    level: &Level,
+    colors: &Colors,
+) -> String {
+    let outcome = match (result.validity(level), result.seat_winner()) {
+        (DistrictValidity::TooSmall, _) => "too small".to_string(),
+        (DistrictValidity::TooBig, _) => "too big".to_string(),
+        (DistrictValidity::NonContiguous, _) => "non-contiguous".to_string(),
+        (DistrictValidity::Valid, Some(DistrictWinner::Good)) => {
+            format!("{} wins", colors.good_color_name)
+        }
+        (DistrictValidity::Valid, _) => format!("{} wins", colors.bad_color_name),
+    };
+    let tie = if result.winner == Some(DistrictWinner::Tie) {
+        " after a tie"
+    } else {
+        ""
+    };
+    format!(
+        "District {}: {} {}, {} {}: {}{}",
+        district_id + 1,
+        colors.good_color_name,
+        result.good_votes,
+        colors.bad_color_name,
+        result.bad_votes,
+        outcome,
+        tie
+    )
+}
+
+/// Writes the shapes marking the provided tile's party, matching the patterns on the in-game map
+fn write_tile_pattern(svg: &mut String, tile: &MapTile, x: usize, y: usize, color: Color) {
+    let dot_size = SVG_TILE_SIZE / 4;
+    let positions = match tile.content {
+        MapTileContent::Good => vec![(dot_size * 3 / 2, dot_size * 3 / 2)],
+        MapTileContent::Bad => vec![
+            (0, 0),
+            (SVG_TILE_SIZE - dot_size, 0),
+            (0, SVG_TILE_SIZE - dot_size),
+            (SVG_TILE_SIZE - dot_size, SVG_TILE_SIZE - dot_size),
+        ],
+        MapTileContent::Empty => vec![],
+    };
+    for (dot_x, dot_y) in positions {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{dot_size}" height="{dot_size}" fill="{}"/>"#,
+            x + dot_x,
+            y + dot_y,
+            svg_color(color)
+        );
+    }
+}
+
+/// Builds SVG path data tracing the outline of the provided district, with one closed loop per boundary.
+/// Only tile edges that aren't shared with another tile in the district are kept, and since they all go clockwise
+/// around their tiles, they join up end to end.
+fn district_outline(map: &Map, district_id: u8) -> String {
+    let mut edges: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+    for tile in map.get_tiles_in_district(district_id) {
+        let (x, y) = (tile.coords.x, tile.coords.y);
+        let in_district = |neighbor: Option<&MapTile>| {
+            neighbor.map_or(false, |n| n.district_id == Some(district_id))
+        };
+        let sides = [
+            (map.get_up(&tile.coords), (x, y), (x + 1, y)),
+            (map.get_right(&tile.coords), (x + 1, y), (x + 1, y + 1)),
+            (map.get_down(&tile.coords), (x + 1, y + 1), (x, y + 1)),
+            (map.get_left(&tile.coords), (x, y + 1), (x, y)),
+        ];
+        for (neighbor, start, end) in sides {
+            if !in_district(neighbor) {
+                edges.entry(start).or_default().push(end);
+            }
+        }
+    }
+
+    let mut path = String::new();
+    let mut starts = edges.keys().copied().collect::<Vec<(usize, usize)>>();
+    starts.sort_unstable();
+    for start in starts {
+        while edges.get(&start).map_or(false, |ends| !ends.is_empty()) {
+            let mut points = vec![start];
+            let mut current = start;
+            while let Some(next) = edges.get_mut(&current).and_then(|ends| ends.pop()) {
+                points.push(next);
+                current = next;
+                if current == start {
+                    break;
+                }
+            }
+
+            // only the corners of the loop are needed
+            let vertices = &points[..points.len() - 1];
+            let num_vertices = vertices.len();
+            let corners = (0..num_vertices)
+                .filter(|i| {
+                    let prev = vertices[(i + num_vertices - 1) % num_vertices];
+                    let next = vertices[(i + 1) % num_vertices];
+                    prev.0 != next.0 && prev.1 != next.1
+                })
+                .map(|i| vertices[i])
+                .collect::<Vec<(usize, usize)>>();
+            for (i, (x, y)) in corners.iter().enumerate() {
+                let command = if i == 0 { 'M' } else { 'L' };
+                let _ = write!(
+                    path,
+                    "{}{} {} ",
+                    command,
+                    x * SVG_TILE_SIZE,
+                    y * SVG_TILE_SIZE
+                );
+            }
+            path.push_str("Z ");
+        }
+    }
+
+    path.trim_end().to_string()
+}
+
+/// Formats the provided color as an SVG hex color
+fn svg_color(color: Color) -> String {
+    let [r, g, b, _] = color.as_rgba_f32();
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(r), channel(g), channel(b))
+}
@@ -0,0 +1,428 @@
+use super::*;
+
+const MAX_BRUSH_SIZE: u8 = 4;
+const RECTANGLE_PREVIEW_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.4);
+
+/// The ways the player can paint tiles
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// Paints a square of tiles under the cursor
+    Brush,
+    /// Paints the connected region of tiles that are in the same district as the clicked tile
+    FloodFill,
+    /// Paints a rectangle of tiles, from where the cursor was pressed to where it was released
+    Rectangle,
+    /// Merges the clicked district into the selected district
+    Merge,
+    /// Splits the selected district in two along a line, from where the cursor was pressed to where it was released
+    Split,
+}
+
+pub struct SelectedTool(pub Tool);
+
+/// How big the brush is. A size of 1 paints a single tile, 2 paints a 3x3 square, and so on.
+pub struct BrushSize(pub u8);
+
+/// Whether painting erases tiles instead, so players without a right mouse button can erase
+pub struct EraseMode(pub bool);
+
+/// Whether the player is painting or erasing this frame, with either the mouse or a touch
+#[derive(Default)]
+pub struct PaintInput {
+    painting: bool,
+    erasing: bool,
+    was_painting: bool,
+    was_erasing: bool,
+}
+
+impl PaintInput {
+    /// Moves on to the next frame, where the player is painting and erasing as provided
+    pub fn update(&mut self, painting: bool, erasing: bool) {
+        self.was_painting = self.painting;
+        self.was_erasing = self.erasing;
+        self.painting = painting;
+        self.erasing = erasing;
+    }
+
+    pub fn pressed(&self) -> bool {
+        self.painting || self.erasing
+    }
+
+    pub fn just_pressed(&self) -> bool {
+        self.pressed() && !(self.was_painting || self.was_erasing)
+    }
+
+    pub fn just_released(&self) -> bool {
+        !self.pressed() && (self.was_painting || self.was_erasing)
+    }
+
+    /// Gets the district to put tiles in based on whether the player is (or just was) painting or erasing
+    pub fn district_id(&self, selected_district: &SelectedDistrict) -> Option<u8> {
+        if self.erasing || (!self.painting && self.was_erasing) {
+            None
+        } else {
+            Some(selected_district.0)
+        }
+    }
+}
+
+/// Keeps track of a rectangle being dragged out with the rectangle tool
+#[derive(Default)]
+pub struct RectangleDrag {
+    pub start: Option<Coordinates>,
+    pub end: Option<Coordinates>,
+}
+
+/// Keeps track of where the cursor has been during a brush stroke, so fast movements don't skip any tiles
+#[derive(Default)]
+pub struct Stroke {
+    last_position: Option<Vec2>,
+    last_coords: Option<Coordinates>,
+}
+
+impl Stroke {
+    /// Moves the stroke to the provided cursor position, and gets the coordinates of every tile it crossed on the way, in order.
+    /// `pick_tile` finds the tile at a position, and `tile_size` is the size of a tile in the same units as the positions.
+    pub fn advance(
+        &mut self,
+        position: Option<Vec2>,
+        tile_size: f32,
+        pick_tile: impl Fn(Vec2) -> Option<Coordinates>,
+    ) -> Vec<Coordinates> {
+        let position = match position {
+            Some(position) => position,
+            None => {
+                // don't connect the stroke across wherever the cursor went while it was off the map
+                *self = Stroke::default();
+                return Vec::new();
+            }
+        };
+
+        // sample the line from the last position often enough that every tile along it gets hit
+        let start = self.last_position.unwrap_or(position);
+        let step = (tile_size / 4.0).max(1.0);
+        let num_steps = (start.distance(position) / step).ceil().max(1.0) as usize;
+        let mut crossed = Vec::new();
+        for i in 1..=num_steps {
+            let point = start.lerp(position, i as f32 / num_steps as f32);
+            let coords = match pick_tile(point) {
+                Some(coords) => coords,
+                None => {
+                    self.last_coords = None;
+                    continue;
+                }
+            };
+            if self.last_coords.as_ref() == Some(&coords) {
+                continue;
+            }
+
+            // fill in corners so the stroke is contiguous even when it moves diagonally between tiles
+            if let Some(last_coords) = &self.last_coords {
+                if last_coords.x != coords.x && last_coords.y != coords.y {
+                    crossed.push(Coordinates {
+                        x: coords.x,
+                        y: last_coords.y,
+                    });
+                }
+            }
+
+            crossed.push(coords.clone());
+            self.last_coords = Some(coords);
+        }
+
+        self.last_position = Some(position);
+        crossed
+    }
+}
+
+#[derive(Component)]
+pub struct ToolSelector(Tool);
+
+#[derive(Component)]
+pub enum BrushSizeButton {
+    Smaller,
+    Bigger,
+}
+
+#[derive(Component)]
+pub struct BrushSizeText;
+
+#[derive(Component)]
+pub struct EraseModeButton;
+
+#[derive(Component)]
+pub struct RectanglePreview;
+
+/// Paints a square of tiles centered on the provided coordinates
+pub fn paint_brush(
+    map: &mut Map,
+    history: &mut History,
+    center: &Coordinates,
+    size: u8,
+    district_id: Option<u8>,
+) {
+    let reach = size.saturating_sub(1) as usize;
+    let corner = Coordinates {
+        x: center.x.saturating_sub(reach),
+        y: center.y.saturating_sub(reach),
+    };
+    let opposite_corner = Coordinates {
+        x: center.x + reach,
+        y: center.y + reach,
+    };
+    fill_rectangle(map, history, &corner, &opposite_corner, district_id);
+}
+
+/// Paints every tile in the rectangle with the provided corners
+pub fn fill_rectangle(
+    map: &mut Map,
+    history: &mut History,
+    corner: &Coordinates,
+    opposite_corner: &Coordinates,
+    district_id: Option<u8>,
+) {
+    let max_y = corner.y.max(opposite_corner.y).min(map.tiles.len() - 1);
+    let max_x = corner.x.max(opposite_corner.x).min(map.tiles[0].len() - 1);
+    for y in corner.y.min(opposite_corner.y)..=max_y {
+        for x in corner.x.min(opposite_corner.x)..=max_x {
+            history.assign(map, &Coordinates { x, y }, district_id);
+        }
+    }
+}
+
+/// Paints the connected region of tiles that are in the same district as the tile with the provided coordinates
+pub fn flood_fill(
+    map: &mut Map,
+    history: &mut History,
+    start: &Coordinates,
+    district_id: Option<u8>,
+) {
+    let target_district_id = map.get(start).district_id;
+    if target_district_id == district_id {
+        return;
+    }
+
+    let mut to_visit = vec![start.clone()];
+    while let Some(coords) = to_visit.pop() {
+        if map.get(&coords).district_id != target_district_id {
+            continue;
+        }
+        history.assign(map, &coords, district_id);
+
+        to_visit.extend(
+            [
+                map.get_up(&coords),
+                map.get_down(&coords),
+                map.get_left(&coords),
+                map.get_right(&coords),
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|tile| tile.district_id == target_district_id)
+            .map(|tile| tile.coords.clone()),
+        );
+    }
+}
+
+/// Spawns the tool palette
+pub fn spawn_tool_palette(parent: &mut ChildBuilder, font: &Handle<Font>, brush_size: &BrushSize) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_button(
+                parent,
+                font,
+                "Brush",
+                SMALL_BUTTON,
+                ToolSelector(Tool::Brush),
+            );
+            spawn_button(
+                parent,
+                font,
+                "Fill",
+                SMALL_BUTTON,
+                ToolSelector(Tool::FloodFill),
+            );
+            spawn_button(
+                parent,
+                font,
+                "Rect",
+                SMALL_BUTTON,
+                ToolSelector(Tool::Rectangle),
+            );
+            spawn_button(parent, font, "Erase", SMALL_BUTTON, EraseModeButton);
+        });
+
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_button(
+                parent,
+                font,
+                "Merge",
+                SMALL_BUTTON,
+                ToolSelector(Tool::Merge),
+            );
+            spawn_button(
+                parent,
+                font,
+                "Split",
+                SMALL_BUTTON,
+                ToolSelector(Tool::Split),
+            );
+        });
+
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_button(parent, font, "-", SMALL_BUTTON, BrushSizeButton::Smaller);
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        brush_size_text(brush_size),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 20.0,
+                            color: Color::SEA_GREEN,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(BrushSizeText);
+            spawn_button(parent, font, "+", SMALL_BUTTON, BrushSizeButton::Bigger);
+        });
+}
+
+fn brush_size_text(brush_size: &BrushSize) -> String {
+    format!("Size {}", brush_size.0)
+}
+
+/// Handles selecting which tool to paint with
+pub fn tool_selection_system(
+    mut selected_tool: ResMut<SelectedTool>,
+    interaction_query: Query<(&Interaction, &ToolSelector), Changed<Interaction>>,
+    mut button_query: Query<(&ToolSelector, &mut UiColor)>,
+) {
+    for (interaction, tool_selector) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            selected_tool.0 = tool_selector.0;
+        }
+    }
+
+    for (tool_selector, mut color) in button_query.iter_mut() {
+        if selected_tool.0 == tool_selector.0 {
+            *color = Color::WHITE.into();
+        } else {
+            *color = NORMAL_BUTTON.into();
+        }
+    }
+}
+
+/// Handles turning erase mode on and off
+pub fn erase_mode_system(
+    mut erase_mode: ResMut<EraseMode>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<EraseModeButton>)>,
+    mut button_query: Query<&mut UiColor, With<EraseModeButton>>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            erase_mode.0 = !erase_mode.0;
+        }
+    }
+
+    for mut color in button_query.iter_mut() {
+        if erase_mode.0 {
+            *color = Color::WHITE.into();
+        } else {
+            *color = NORMAL_BUTTON.into();
+        }
+    }
+}
+
+/// Handles changing the brush size
+pub fn brush_size_system(
+    mut brush_size: ResMut<BrushSize>,
+    interaction_query: Query<(&Interaction, &BrushSizeButton), Changed<Interaction>>,
+    mut text_query: Query<&mut Text, With<BrushSizeText>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            brush_size.0 = match button {
+                BrushSizeButton::Smaller => brush_size.0.saturating_sub(1).max(1),
+                BrushSizeButton::Bigger => (brush_size.0 + 1).min(MAX_BRUSH_SIZE),
+            };
+        }
+    }
+
+    if brush_size.is_changed() {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = brush_size_text(&brush_size);
+        }
+    }
+}
+
+/// Handles showing the rectangle being dragged out with the rectangle tool
+pub fn rectangle_preview_system(
+    mut commands: Commands,
+    rectangle_drag: Res<RectangleDrag>,
+    layout: Res<MapLayout>,
+    mut preview_query: Query<(Entity, &mut Transform), With<RectanglePreview>>,
+) {
+    let (start, end) = match (&rectangle_drag.start, &rectangle_drag.end) {
+        (Some(start), Some(end)) => (layout.tile_position(start), layout.tile_position(end)),
+        _ => {
+            for (entity, _) in preview_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        }
+    };
+
+    let translation = ((start + end) / 2.0).truncate().extend(4.0);
+    let scale = ((start - end).abs() + layout.tile_size)
+        .truncate()
+        .extend(1.0);
+    if let Ok((_, mut transform)) = preview_query.get_single_mut() {
+        transform.translation = translation;
+        transform.scale = scale;
+    } else {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: RECTANGLE_PREVIEW_COLOR,
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation,
+                    scale,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(GameComponent)
+            .insert(RectanglePreview);
+    }
+}
@@ -0,0 +1,184 @@
+use super::*;
+
+const TOOLTIP_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.85);
+/// How far the tooltip is from the cursor, in pixels
+const TOOLTIP_OFFSET: f32 = 15.0;
+/// About how wide the tooltip gets, in pixels, so it can be kept on screen
+const TOOLTIP_WIDTH: f32 = 300.0;
+/// About how tall the tooltip gets, in pixels, so it can be kept on screen
+const TOOLTIP_HEIGHT: f32 = 150.0;
+
+#[derive(Component)]
+pub struct Tooltip;
+
+#[derive(Component)]
+pub struct TooltipText;
+
+/// Spawns the tooltip shown when hovering over the map, hidden until it's needed
+pub fn spawn_tooltip(commands: &mut Commands, font: &Handle<Font>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                padding: Rect::all(Val::Px(5.0)),
+                display: Display::None,
+                ..Default::default()
+            },
+            color: UiColor(TOOLTIP_BACKGROUND_COLOR),
+            ..Default::default()
+        })
+        .insert(GameComponent)
+        .insert(Tooltip)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(TooltipText);
+        });
+}
+
+/// Builds the text describing the provided tile and the district it's in
+fn tooltip_text(
+    tile: &MapTile,
+    results: &[DistrictResult],
+    level: &Level,
+    colors: &Colors,
+) -> String {
+    let mut text = format!("Tile ({}, {})\n", tile.coords.x + 1, tile.coords.y + 1);
+    text.push_str(&match tile.content {
+        MapTileContent::Good => format!(
+            "1 {} voter, {}% turnout\n",
+            colors.good_color_name, tile.turnout
+        ),
+        MapTileContent::Bad => format!(
+            "1 {} voter, {}% turnout\n",
+            colors.bad_color_name, tile.turnout
+        ),
+        MapTileContent::Empty => "No voters\n".to_string(),
+    });
+
+    let district_id = match tile.district_id {
+        Some(district_id) => district_id,
+        None => {
+            text.push_str("Not in a district");
+            return text;
+        }
+    };
+
+    let result = match results.get(district_id as usize) {
+        Some(result) => result,
+        None => return text,
+    };
+    let margin = result.good_votes as isize - result.bad_votes as isize;
+    let validity = match result.validity(level) {
+        DistrictValidity::TooSmall => format!(
+            "too small (needs {} to {})",
+            level.min_district_size, level.max_district_size
+        ),
+        DistrictValidity::TooBig => format!(
+            "too big (needs {} to {})",
+            level.min_district_size, level.max_district_size
+        ),
+        DistrictValidity::NonContiguous => "non-contiguous".to_string(),
+        DistrictValidity::Valid => "valid".to_string(),
+    };
+    text.push_str(&format!(
+        "District {} ({} voters, {})\n{}: {}  {}: {}\nMargin: {:+}",
+        district_id + 1,
+        result.size,
+        validity,
+        colors.good_color_name,
+        result.good_votes,
+        colors.bad_color_name,
+        result.bad_votes,
+        margin,
+    ));
+    if result.winner == Some(DistrictWinner::Tie) {
+        text.push_str(&format!(
+            " (tie, {} by {})",
+            tie_outcome(result),
+            level.tie_rule.description()
+        ));
+    }
+    if let Some(class) = MarginClass::of(result) {
+        text.push_str(&format!(" [{}]", class.description()));
+    }
+
+    text
+}
+
+/// Handles showing details about the tile under the cursor
+#[allow(clippy::too_many_arguments)]
+pub fn tooltip_system(
+    windows: Res<Windows>,
+    cursor_position: Res<CursorPosition>,
+    layout: Res<MapLayout>,
+    map: Res<Map>,
+    level: Res<Level>,
+    results: Res<DistrictResults>,
+    colors: Res<Colors>,
+    mut tooltip_query: Query<&mut Style, With<Tooltip>>,
+    mut text_query: Query<&mut Text, With<TooltipText>>,
+) {
+    let screen_position = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position().map(|pos| (pos, window)));
+    let hovered_tile = cursor_position
+        .0
+        .and_then(|pos| layout.coords_at(pos))
+        .map(|coords| map.get(&coords));
+
+    let mut style = match tooltip_query.get_single_mut() {
+        Ok(style) => style,
+        Err(_) => return,
+    };
+
+    let ((screen_pos, window), tile) = match (screen_position, hovered_tile) {
+        (Some(screen_position), Some(tile)) => (screen_position, tile),
+        _ => {
+            // only touch the style when it changes, since that makes the UI lay itself out again
+            if style.display != Display::None {
+                style.display = Display::None;
+            }
+            return;
+        }
+    };
+
+    // keep the tooltip on screen by putting it on the other side of the cursor near the edges
+    let position = Rect {
+        left: if screen_pos.x + TOOLTIP_OFFSET + TOOLTIP_WIDTH > window.width() {
+            Val::Px(screen_pos.x - TOOLTIP_OFFSET - TOOLTIP_WIDTH)
+        } else {
+            Val::Px(screen_pos.x + TOOLTIP_OFFSET)
+        },
+        bottom: if screen_pos.y - TOOLTIP_OFFSET - TOOLTIP_HEIGHT < 0.0 {
+            Val::Px(screen_pos.y + TOOLTIP_OFFSET)
+        } else {
+            Val::Px(screen_pos.y - TOOLTIP_OFFSET - TOOLTIP_HEIGHT)
+        },
+        ..Default::default()
+    };
+    if style.display != Display::Flex {
+        style.display = Display::Flex;
+    }
+    if style.position != position {
+        style.position = position;
+    }
+
+    let value = tooltip_text(tile, &results.0, &level, &colors);
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use super::*;
+
+/// How far a touch has to move before it starts a stroke, in pixels
+const TOUCH_DRAG_THRESHOLD: f32 = 10.0;
+/// How long a touch has to be held in place to start erasing, in seconds
+const LONG_PRESS_DURATION: f64 = 0.5;
+
+/// What the current touch gesture is doing
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TouchGestureKind {
+    /// The touch hasn't moved or been held long enough to tell yet
+    Pending,
+    Paint,
+    Erase,
+    /// More than one finger is down, so the map is being zoomed instead of painted
+    Pinch,
+}
+
+/// Keeps track of the touch gesture in progress, if there is one
+#[derive(Default)]
+pub struct TouchGesture {
+    kind: Option<TouchGestureKind>,
+    started_at: f64,
+    /// Whether the gesture ended as a tap this frame, and so should paint for just this frame
+    tapped: bool,
+}
+
+/// Handles figuring out whether the player is painting or erasing, with either the mouse or a touch
+pub fn paint_input_system(
+    time: Res<Time>,
+    buttons: Res<Input<MouseButton>>,
+    controls: Res<Controls>,
+    touches: Res<Touches>,
+    erase_mode: Res<EraseMode>,
+    mut gesture: ResMut<TouchGesture>,
+    mut paint_input: ResMut<PaintInput>,
+) {
+    let now = time.seconds_since_startup();
+    let num_touches = touches.iter().count();
+    let was_tapped = gesture.tapped;
+    gesture.tapped = false;
+
+    if num_touches > 1 {
+        gesture.kind = Some(TouchGestureKind::Pinch);
+    } else if let Some(touch) = touches.iter().next() {
+        match gesture.kind {
+            None => {
+                gesture.kind = Some(TouchGestureKind::Pending);
+                gesture.started_at = now;
+            }
+            Some(TouchGestureKind::Pending) => {
+                if touch.distance().length() > TOUCH_DRAG_THRESHOLD {
+                    gesture.kind = Some(if erase_mode.0 {
+                        TouchGestureKind::Erase
+                    } else {
+                        TouchGestureKind::Paint
+                    });
+                } else if now - gesture.started_at >= LONG_PRESS_DURATION {
+                    gesture.kind = Some(TouchGestureKind::Erase);
+                }
+            }
+            _ => (),
+        }
+    } else {
+        // a touch that's lifted before it turns into anything else is a tap
+        gesture.tapped = gesture.kind == Some(TouchGestureKind::Pending) && !was_tapped;
+        gesture.kind = None;
+    }
+
+    let touch_paint =
+        gesture.kind == Some(TouchGestureKind::Paint) || (gesture.tapped && !erase_mode.0);
+    let touch_erase =
+        gesture.kind == Some(TouchGestureKind::Erase) || (gesture.tapped && erase_mode.0);
+
+    // the right button pans instead of erasing if it's been bound to that
+    let right_button_pans =
+        controls.bindings[&Action::DragPan].mouse_button == Some(MouseButton::Right);
+    let mouse_paint = buttons.pressed(MouseButton::Left) && !erase_mode.0;
+    let mouse_erase = (buttons.pressed(MouseButton::Right) && !right_button_pans)
+        || (buttons.pressed(MouseButton::Left) && erase_mode.0);
+
+    paint_input.update(mouse_paint || touch_paint, mouse_erase || touch_erase);
+}
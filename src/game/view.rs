@@ -0,0 +1,251 @@
+use super::*;
+
+const UNASSIGNED_TILE_COLOR: Color = EMPTY_TILE_COLOR;
+const VALID_DISTRICT_COLOR: Color = Color::rgb(0.7, 0.85, 0.7);
+const TOO_SMALL_DISTRICT_COLOR: Color = Color::ORANGE;
+const TOO_BIG_DISTRICT_COLOR: Color = Color::ORANGE_RED;
+const NON_CONTIGUOUS_DISTRICT_COLOR: Color = Color::PURPLE;
+/// The margin of victory, as a fraction of the district's voters, at which a district is shown in its winner's full color
+const LANDSLIDE_MARGIN: f32 = 0.5;
+/// The margin, as a fraction of the district's voters, the bad party needs for a district to count as packed
+const PACKED_MARGIN: f32 = 0.4;
+/// The margin, as a fraction of the district's voters, below which the good party's win is at risk
+const NARROW_MARGIN: f32 = 0.15;
+/// How much the margin overlay covers the view underneath it
+const MARGIN_OVERLAY_STRENGTH: f32 = 0.7;
+const PACKED_DISTRICT_COLOR: Color = Color::rgb(0.45, 0.1, 0.55);
+const LOST_DISTRICT_COLOR: Color = Color::rgb(0.6, 0.6, 0.6);
+const NARROW_DISTRICT_COLOR: Color = Color::rgb(1.0, 0.85, 0.0);
+const SAFE_DISTRICT_COLOR: Color = Color::rgb(0.0, 0.45, 0.7);
+/// The hue difference between neighboring district IDs, which keeps the colors far apart no matter how many districts there are
+const DISTRICT_HUE_STEP: f32 = 137.5;
+
+/// The ways the map can be colored
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ViewMode {
+    /// Colors each tile by the party its voter supports
+    Voters,
+    /// Gives each district its own color
+    Districts,
+    /// Colors each district by who wins it, more strongly the bigger the margin
+    Results,
+    /// Highlights districts that break the level's rules
+    Validity,
+}
+
+impl ViewMode {
+    const ALL: [ViewMode; 4] = [
+        ViewMode::Voters,
+        ViewMode::Districts,
+        ViewMode::Results,
+        ViewMode::Validity,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ViewMode::Voters => "Votes",
+            ViewMode::Districts => "Dists",
+            ViewMode::Results => "Wins",
+            ViewMode::Validity => "Valid",
+        }
+    }
+
+    fn next(&self) -> Self {
+        let idx = ViewMode::ALL
+            .iter()
+            .position(|mode| mode == self)
+            .unwrap_or(0);
+        ViewMode::ALL[(idx + 1) % ViewMode::ALL.len()]
+    }
+}
+
+pub struct SelectedView(pub ViewMode);
+
+/// Whether districts are tinted by how safe the good party's margin in them is, on top of the selected view
+pub struct MarginOverlay(pub bool);
+
+#[derive(Component)]
+pub struct ViewSelector(ViewMode);
+
+#[derive(Component)]
+pub struct MarginOverlayButton;
+
+/// How big a district's margin is, from the good party's point of view
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MarginClass {
+    /// The bad party wins by a huge margin, wasting lots of its votes
+    Packed,
+    /// The bad party wins by a normal margin
+    Lost,
+    /// The good party wins, but by so little that the district could easily flip
+    Narrow,
+    /// The good party wins comfortably
+    Safe,
+}
+
+impl MarginClass {
+    /// Determines the margin class of the provided district, if it has any voters
+    pub fn of(result: &DistrictResult) -> Option<Self> {
+        let total_votes = result.good_votes + result.bad_votes;
+        if total_votes == 0 {
+            return None;
+        }
+
+        let margin = (result.good_votes as f32 - result.bad_votes as f32) / total_votes as f32;
+        let class = match result.seat_winner() {
+            Some(DistrictWinner::Good) if margin < NARROW_MARGIN => MarginClass::Narrow,
+            Some(DistrictWinner::Good) => MarginClass::Safe,
+            _ if margin <= -PACKED_MARGIN => MarginClass::Packed,
+            _ => MarginClass::Lost,
+        };
+        Some(class)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            MarginClass::Packed => "packed",
+            MarginClass::Lost => "lost",
+            MarginClass::Narrow => "narrow win",
+            MarginClass::Safe => "safe win",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            MarginClass::Packed => PACKED_DISTRICT_COLOR,
+            MarginClass::Lost => LOST_DISTRICT_COLOR,
+            MarginClass::Narrow => NARROW_DISTRICT_COLOR,
+            MarginClass::Safe => SAFE_DISTRICT_COLOR,
+        }
+    }
+}
+
+/// Determines what color to fill the provided tile with in the provided view
+pub fn tile_fill_color(
+    tile: &MapTile,
+    view: ViewMode,
+    results: &[DistrictResult],
+    level: &Level,
+    colors: &Colors,
+) -> Color {
+    match (view, tile.district_id) {
+        (ViewMode::Voters, Some(_)) => match tile.content {
+            MapTileContent::Good => colors.good_faded,
+            MapTileContent::Bad => colors.bad_faded,
+            MapTileContent::Empty => EMPTY_TILE_COLOR_FADED,
+        },
+        (ViewMode::Voters, None) => tile.color(colors),
+        (_, None) => UNASSIGNED_TILE_COLOR,
+        (ViewMode::Districts, Some(district_id)) => {
+            Color::hsl((district_id as f32 * DISTRICT_HUE_STEP) % 360.0, 0.6, 0.65)
+        }
+        (ViewMode::Results, Some(district_id)) => {
+            let result = &results[district_id as usize];
+            let (faded, regular) = match result.seat_winner() {
+                Some(DistrictWinner::Good) => (colors.good_faded, colors.good_regular),
+                Some(DistrictWinner::Bad) => (colors.bad_faded, colors.bad_regular),
+                _ => return UNASSIGNED_TILE_COLOR,
+            };
+            let margin = (result.good_votes as f32 - result.bad_votes as f32).abs()
+                / (result.good_votes + result.bad_votes).max(1) as f32;
+            mix_colors(faded, regular, (margin / LANDSLIDE_MARGIN).min(1.0))
+        }
+        (ViewMode::Validity, Some(district_id)) => {
+            match results[district_id as usize].validity(level) {
+                DistrictValidity::Valid => VALID_DISTRICT_COLOR,
+                DistrictValidity::TooSmall => TOO_SMALL_DISTRICT_COLOR,
+                DistrictValidity::TooBig => TOO_BIG_DISTRICT_COLOR,
+                DistrictValidity::NonContiguous => NON_CONTIGUOUS_DISTRICT_COLOR,
+            }
+        }
+    }
+}
+
+/// Tints the provided fill color of a tile by the margin of the district it's in, if it's in one
+pub fn apply_margin_overlay(
+    fill_color: Color,
+    tile: &MapTile,
+    results: &[DistrictResult],
+) -> Color {
+    let class = tile
+        .district_id
+        .and_then(|district_id| MarginClass::of(&results[district_id as usize]));
+    match class {
+        Some(class) => mix_colors(fill_color, class.color(), MARGIN_OVERLAY_STRENGTH),
+        None => fill_color,
+    }
+}
+
+/// Blends between two colors, from all `a` at 0 to all `b` at 1
+fn mix_colors(a: Color, b: Color, amount: f32) -> Color {
+    let [a_r, a_g, a_b, a_a] = a.as_rgba_f32();
+    let [b_r, b_g, b_b, b_a] = b.as_rgba_f32();
+    let mix = |a: f32, b: f32| a + (b - a) * amount;
+    Color::rgba(mix(a_r, b_r), mix(a_g, b_g), mix(a_b, b_b), mix(a_a, b_a))
+}
+
+/// Spawns the view mode buttons
+pub fn spawn_view_buttons(parent: &mut ChildBuilder, font: &Handle<Font>) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for mode in ViewMode::ALL {
+                spawn_button(parent, font, mode.label(), SMALL_BUTTON, ViewSelector(mode));
+            }
+            spawn_button(parent, font, "Heat", SMALL_BUTTON, MarginOverlayButton);
+        });
+}
+
+/// Handles switching between view modes
+pub fn view_selection_system(
+    mut selected_view: ResMut<SelectedView>,
+    actions: Res<Actions>,
+    interaction_query: Query<(&Interaction, &ViewSelector), Changed<Interaction>>,
+    mut button_query: Query<(&ViewSelector, &mut UiColor)>,
+) {
+    for (interaction, view_selector) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            selected_view.0 = view_selector.0;
+        }
+    }
+
+    if actions.just_pressed(Action::NextView) {
+        selected_view.0 = selected_view.0.next();
+    }
+
+    for (view_selector, mut color) in button_query.iter_mut() {
+        if selected_view.0 == view_selector.0 {
+            *color = Color::WHITE.into();
+        } else {
+            *color = NORMAL_BUTTON.into();
+        }
+    }
+}
+
+/// Handles turning the margin overlay on and off
+pub fn margin_overlay_system(
+    mut margin_overlay: ResMut<MarginOverlay>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<MarginOverlayButton>)>,
+    mut button_query: Query<&mut UiColor, With<MarginOverlayButton>>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            margin_overlay.0 = !margin_overlay.0;
+        }
+    }
+
+    for mut color in button_query.iter_mut() {
+        if margin_overlay.0 {
+            *color = Color::WHITE.into();
+        } else {
+            *color = NORMAL_BUTTON.into();
+        }
+    }
+}
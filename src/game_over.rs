@@ -5,17 +5,153 @@ pub struct GameOverPlugin;
 impl Plugin for GameOverPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(game_over_setup))
+            .add_system_set(
+                SystemSet::on_update(GameState::GameOver).with_system(game_over_button_system),
+            )
             .add_system_set(
                 SystemSet::on_exit(GameState::GameOver)
                     .with_system(despawn_components_system::<GameOverComponent>),
-            );
+            )
+            .insert_resource(GameOverStart(0.0));
     }
 }
 
+/// How long to wait before the confirm action tries again, so the press that left the results doesn't also skip this screen
+const CONFIRM_DELAY_SECONDS: f64 = 0.5;
+
+const GAME_OVER_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(250.0),
+        height: Val::Px(80.0),
+    },
+    margin: 15.0,
+    font_size: 40.0,
+};
+
 #[derive(Component)]
 struct GameOverComponent;
 
+/// When the game over screen was shown, in seconds since startup
+struct GameOverStart(f64);
+
+#[derive(Component, Clone, Copy)]
+enum GameOverButton {
+    Retry,
+    Menu,
+}
+
 /// Sets up the game over screen.
-fn game_over_setup() {
-    todo!(); //TODO
+fn game_over_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut start: ResMut<GameOverStart>,
+    score: Res<Score>,
+    stats: Res<RunStats>,
+) {
+    start.0 = time.seconds_since_startup();
+
+    let font = asset_server.load(MAIN_FONT);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::ColumnReverse,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(GameOverComponent)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    "Voted out",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 70.0,
+                        color: Color::WHITE,
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    format!(
+                        "Years in power: {}\nHighest level reached: {}\nElections won: {}\nSeats won: {}\nSeats lost: {}\nLargest map: {}x{}",
+                        score.0,
+                        stats.elections_won + 1,
+                        stats.elections_won,
+                        stats.seats_won,
+                        stats.seats_lost,
+                        stats.largest_map_size,
+                        stats.largest_map_size,
+                    ),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 30.0,
+                        color: Color::SEA_GREEN,
+                    },
+                    TextAlignment {
+                        horizontal: HorizontalAlign::Center,
+                        ..Default::default()
+                    },
+                ),
+                style: Style {
+                    margin: Rect::all(Val::Px(20.0)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        ..Default::default()
+                    },
+                    color: UiColor(Color::NONE),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for (label, button) in [
+                        ("Try again", GameOverButton::Retry),
+                        ("Main menu", GameOverButton::Menu),
+                    ] {
+                        spawn_button(parent, &font, label, GAME_OVER_BUTTON, button);
+                    }
+                });
+        });
+}
+
+/// Handles interactions with the game over buttons. Confirming without a mouse tries again.
+fn game_over_button_system(
+    mut game_state: ResMut<State<GameState>>,
+    actions: Res<Actions>,
+    time: Res<Time>,
+    start: Res<GameOverStart>,
+    interaction_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+) {
+    let can_confirm = time.seconds_since_startup() - start.0 > CONFIRM_DELAY_SECONDS;
+    let mut pressed = if can_confirm && actions.just_pressed(Action::Confirm) {
+        Some(GameOverButton::Retry)
+    } else {
+        None
+    };
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            pressed = Some(*button);
+        }
+    }
+
+    match pressed {
+        Some(GameOverButton::Retry) => game_state.set(GameState::Game).unwrap(),
+        Some(GameOverButton::Menu) => game_state.set(GameState::Menu).unwrap(),
+        None => (),
+    }
 }
@@ -1,6 +1,7 @@
 use bevy::{
     app::AppExit,
     diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    ecs::system::EntityCommands,
     prelude::*,
 };
 use bevy_inspector_egui::{WorldInspectorParams, WorldInspectorPlugin};
@@ -8,6 +9,24 @@ use bevy_inspector_egui::{WorldInspectorParams, WorldInspectorPlugin};
 mod cursor_position;
 use cursor_position::*;
 
+mod controls;
+use controls::*;
+
+mod storage;
+use storage::*;
+
+mod palette;
+use palette::*;
+
+mod ui_scale;
+use ui_scale::*;
+
+mod audio;
+use audio::*;
+
+mod settings;
+use settings::*;
+
 mod menu;
 use menu::*;
 
@@ -32,17 +51,40 @@ const RED: Color = Color::rgb(0.8, 0.0, 0.0);
 const RED_FADED: Color = Color::rgb(1.0, 0.5, 0.5);
 
 pub struct Colors {
+    /// The party the player is in
+    party: Party,
+    palette: Palette,
+    /// Whether tiles and labels get patterns and symbols, so party colors aren't the only way to tell them apart
+    patterns: bool,
     good_color_name: String,
+    bad_color_name: String,
     good_regular: Color,
     good_faded: Color,
     bad_regular: Color,
     bad_faded: Color,
+    /// The color of the label for a tied district
+    tie_label: Color,
+    /// The color of the label for a district with no voters
+    undecided_label: Color,
+}
+
+/// How big a button is, how much space it leaves around itself, and how big its label is
+#[derive(Clone, Copy)]
+struct ButtonStyle {
+    size: Size<Val>,
+    margin: f32,
+    font_size: f32,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
 pub enum GameState {
     Menu,
+    Controls,
     Game,
+    /// Shown on top of the game once the player confirms their plan, while the votes are counted
+    Results,
+    /// Shown on top of the game when the player pauses it
+    Paused,
     GameOver,
 }
 
@@ -63,6 +105,45 @@ fn despawn_components<T: Component>(to_despawn: Query<Entity, With<T>>, commands
     }
 }
 
+/// Spawns a button with the provided label, style, and component
+fn spawn_button<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    font: &Handle<Font>,
+    label: &str,
+    button_style: ButtonStyle,
+    component: impl Component,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut button = parent.spawn_bundle(ButtonBundle {
+        style: Style {
+            size: button_style.size,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: Rect::all(Val::Px(button_style.margin)),
+            ..Default::default()
+        },
+        color: NORMAL_BUTTON.into(),
+        ..Default::default()
+    });
+    button.insert(component).with_children(|parent| {
+        parent.spawn_bundle(TextBundle {
+            text: Text::with_section(
+                label,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: button_style.font_size,
+                    color: Color::SEA_GREEN,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    ..Default::default()
+                },
+            ),
+            ..Default::default()
+        });
+    });
+    button
+}
+
 fn setup(mut commands: Commands) {
     // cameras
     commands
@@ -89,7 +170,6 @@ fn button_color_system(
 type InteractedExitButtonTuple = (Changed<Interaction>, With<ExitButton>);
 
 /// Handles interactions with the exit button.
-/// TODO but there isn't an exit button
 fn exit_button_system(
     mut app_exit_events: EventWriter<AppExit>,
     interaction_query: Query<&Interaction, InteractedExitButtonTuple>,
@@ -120,28 +200,26 @@ fn main() {
             height: 720.0,
             ..Default::default()
         })
-        .insert_resource(Colors {
-            good_color_name: "n/a".to_string(),
-            good_regular: Color::WHITE,
-            good_faded: Color::WHITE,
-            bad_regular: Color::WHITE,
-            bad_faded: Color::WHITE,
-        })
+        .add_plugins(DefaultPlugins)
+        .insert_resource(Colors::new(Party::Red, Palette::Standard, false))
         .add_state(GameState::Menu)
         .add_startup_system(setup)
         .add_plugin(CursorPositionPlugin)
+        .add_plugin(ControlsPlugin)
+        .add_plugin(UiScalePlugin)
+        .add_plugin(GameAudioPlugin)
+        .add_plugin(SettingsPlugin)
         .add_plugin(MenuPlugin)
         .add_plugin(GamePlugin)
         .add_plugin(GameOverPlugin)
         .add_system(button_color_system)
-        .add_system(exit_button_system)
-        .add_plugins(DefaultPlugins);
+        .add_system(exit_button_system);
 
     if DEV_MODE {
         app.add_system(bevy::input::system::exit_on_esc_system)
             .add_system(world_inspector_system)
             .add_plugin(LogDiagnosticsPlugin::default())
-            .add_plugin(FrameTimeDiagnosticsPlugin::default())
+            .add_plugin(FrameTimeDiagnosticsPlugin)
             .add_plugin(WorldInspectorPlugin::new())
             .insert_resource(WorldInspectorParams {
                 enabled: false,
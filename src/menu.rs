@@ -9,23 +9,70 @@ impl Plugin for MenuPlugin {
                 SystemSet::on_exit(GameState::Menu)
                     .with_system(despawn_components_system::<MenuComponent>),
             )
-            .add_system(start_button_system);
+            .add_system(continue_button_system)
+            .add_system(start_button_system)
+            .add_system(controls_button_system)
+            .add_system_set(
+                SystemSet::on_update(GameState::Menu).with_system(menu_navigation_system),
+            )
+            .insert_resource(MenuFocus(None));
     }
 }
 
 #[derive(Component)]
 struct MenuComponent;
 
+#[derive(Component)]
+struct ContinueRunButton;
+
 #[derive(Component)]
 struct StartButton(Party);
 
-enum Party {
-    Red,
-    Blue,
+#[derive(Component)]
+struct ControlsButton;
+
+/// The menu buttons, in the order keyboard and gamepad navigation moves through them
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum MenuButton {
+    Continue,
+    Start(Party),
+    Controls,
+    Setting(Setting),
 }
 
+const MENU_BUTTONS: [MenuButton; 8] = [
+    MenuButton::Continue,
+    MenuButton::Start(Party::Red),
+    MenuButton::Start(Party::Blue),
+    MenuButton::Controls,
+    MenuButton::Setting(Setting::Palette),
+    MenuButton::Setting(Setting::Patterns),
+    MenuButton::Setting(Setting::Music),
+    MenuButton::Setting(Setting::Effects),
+];
+
+const MENU_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(250.0),
+        height: Val::Px(100.0),
+    },
+    margin: 15.0,
+    font_size: 40.0,
+};
+
+/// Which menu button is focused for keyboard and gamepad navigation, if any
+struct MenuFocus(Option<usize>);
+
 /// Sets up the main menu screen.
-fn menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn menu_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    colors: Res<Colors>,
+    audio_settings: Res<AudioSettings>,
+    mut menu_focus: ResMut<MenuFocus>,
+) {
+    menu_focus.0 = None;
+
     // title text
     let font = asset_server.load(MAIN_FONT);
     commands
@@ -76,7 +123,7 @@ fn menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 size: Size::new(Val::Percent(100.0), Val::Percent(50.0)),
                 position_type: PositionType::Absolute,
                 position: Rect {
-                    bottom: Val::Px(0.0),
+                    bottom: Val::Px(80.0),
                     ..Default::default()
                 },
                 justify_content: JustifyContent::Center,
@@ -88,70 +135,86 @@ fn menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         })
         .insert(MenuComponent)
         .with_children(|parent| {
-            parent
-                .spawn_bundle(ButtonBundle {
-                    style: Style {
-                        size: Size::new(Val::Px(250.0), Val::Px(100.0)),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: Rect::all(Val::Px(15.0)),
-                        ..Default::default()
-                    },
-                    color: NORMAL_BUTTON.into(),
-                    ..Default::default()
-                })
-                .insert(StartButton(Party::Red))
-                .with_children(|parent| {
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::with_section(
-                            "Join the\nred party",
-                            TextStyle {
-                                font: font.clone(),
-                                font_size: 40.0,
-                                color: Color::SEA_GREEN,
-                            },
-                            TextAlignment {
-                                horizontal: HorizontalAlign::Center,
-                                ..Default::default()
-                            },
-                        ),
-                        ..Default::default()
-                    });
-                });
-
-            parent
-                .spawn_bundle(ButtonBundle {
-                    style: Style {
-                        size: Size::new(Val::Px(250.0), Val::Px(100.0)),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: Rect::all(Val::Px(15.0)),
-                        ..Default::default()
-                    },
-                    color: NORMAL_BUTTON.into(),
+            if saved_run_exists() {
+                spawn_button(parent, &font, "Continue", MENU_BUTTON, ContinueRunButton)
+                    .insert(MenuButton::Continue);
+            }
+
+            spawn_button(
+                parent,
+                &font,
+                "Join the\nred party",
+                MENU_BUTTON,
+                StartButton(Party::Red),
+            )
+            .insert(MenuButton::Start(Party::Red));
+
+            spawn_button(
+                parent,
+                &font,
+                "Join the\nblue party",
+                MENU_BUTTON,
+                StartButton(Party::Blue),
+            )
+            .insert(MenuButton::Start(Party::Blue));
+
+            spawn_button(parent, &font, "Controls", MENU_BUTTON, ControlsButton)
+                .insert(MenuButton::Controls);
+        });
+
+    // settings buttons
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Px(80.0)),
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(0.0),
                     ..Default::default()
-                })
-                .insert(StartButton(Party::Blue))
-                .with_children(|parent| {
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::with_section(
-                            "Join the\nblue party",
-                            TextStyle {
-                                font: font.clone(),
-                                font_size: 40.0,
-                                color: Color::SEA_GREEN,
-                            },
-                            TextAlignment {
-                                horizontal: HorizontalAlign::Center,
-                                ..Default::default()
-                            },
-                        ),
-                        ..Default::default()
-                    });
-                });
+                },
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(MenuComponent)
+        .with_children(|parent| {
+            for setting in Setting::ALL {
+                spawn_setting_button(
+                    parent,
+                    &font,
+                    setting,
+                    &colors,
+                    &audio_settings,
+                    (MenuButton::Setting(setting),),
+                );
+            }
         });
 }
 
+type InteractedContinueRunButtonTuple = (Changed<Interaction>, With<ContinueRunButton>);
+
+/// Handles interactions with the continue button.
+fn continue_button_system(
+    mut game_state: ResMut<State<GameState>>,
+    mut resume_run: ResMut<ResumeRun>,
+    interaction_query: Query<&Interaction, InteractedContinueRunButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            continue_run(&mut resume_run, &mut game_state);
+        }
+    }
+}
+
+/// Picks the saved run back up
+fn continue_run(resume_run: &mut ResumeRun, game_state: &mut State<GameState>) {
+    resume_run.0 = true;
+    game_state.set(GameState::Game).unwrap();
+}
+
 /// Handles interactions with the start buttons.
 fn start_button_system(
     mut game_state: ResMut<State<GameState>>,
@@ -160,23 +223,85 @@ fn start_button_system(
 ) {
     for (interaction, start_button) in interaction_query.iter() {
         if *interaction == Interaction::Clicked {
-            *colors = match start_button.0 {
-                Party::Red => Colors {
-                    good_color_name: "red".to_string(),
-                    good_regular: RED,
-                    good_faded: RED_FADED,
-                    bad_regular: BLUE,
-                    bad_faded: BLUE_FADED,
-                },
-                Party::Blue => Colors {
-                    good_color_name: "blue".to_string(),
-                    good_regular: BLUE,
-                    good_faded: BLUE_FADED,
-                    bad_regular: RED,
-                    bad_faded: RED_FADED,
-                },
-            };
-            game_state.set(GameState::Game).unwrap();
+            start_game(start_button.0, &mut colors, &mut game_state);
+        }
+    }
+}
+
+/// Starts the game as a member of the provided party
+fn start_game(party: Party, colors: &mut Colors, game_state: &mut State<GameState>) {
+    *colors = Colors::new(party, colors.palette, colors.patterns);
+    game_state.set(GameState::Game).unwrap();
+}
+
+type InteractedControlsButtonTuple = (Changed<Interaction>, With<ControlsButton>);
+
+/// Handles interactions with the controls button.
+fn controls_button_system(
+    mut game_state: ResMut<State<GameState>>,
+    interaction_query: Query<&Interaction, InteractedControlsButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            game_state.set(GameState::Controls).unwrap();
+        }
+    }
+}
+
+/// Handles moving between and pressing the menu buttons without a mouse
+fn menu_navigation_system(
+    actions: Res<Actions>,
+    mut menu_focus: ResMut<MenuFocus>,
+    mut game_state: ResMut<State<GameState>>,
+    mut colors: ResMut<Colors>,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut resume_run: ResMut<ResumeRun>,
+    mut button_query: Query<(&MenuButton, &mut UiColor)>,
+) {
+    // the continue button is only there when there's a saved run
+    let menu_buttons = MENU_BUTTONS
+        .into_iter()
+        .filter(|button| {
+            button_query
+                .iter()
+                .any(|(menu_button, _)| menu_button == button)
+        })
+        .collect::<Vec<MenuButton>>();
+    let num_buttons = menu_buttons.len();
+    if num_buttons == 0 {
+        return;
+    }
+
+    if actions.just_pressed(Action::CursorLeft) || actions.just_pressed(Action::CursorUp) {
+        menu_focus.0 = Some(
+            menu_focus
+                .0
+                .map_or(0, |i| (i + num_buttons - 1) % num_buttons),
+        );
+    } else if actions.just_pressed(Action::CursorRight) || actions.just_pressed(Action::CursorDown)
+    {
+        menu_focus.0 = Some(menu_focus.0.map_or(0, |i| (i + 1) % num_buttons));
+    } else if actions.just_pressed(Action::Confirm) {
+        match menu_focus.0.map(|i| menu_buttons[i]) {
+            Some(MenuButton::Continue) => continue_run(&mut resume_run, &mut game_state),
+            Some(MenuButton::Start(party)) => start_game(party, &mut colors, &mut game_state),
+            Some(MenuButton::Controls) => game_state.set(GameState::Controls).unwrap(),
+            Some(MenuButton::Setting(setting)) => setting.change(&mut colors, &mut audio_settings),
+            None => menu_focus.0 = Some(0),
         }
+        return;
+    }
+
+    if !menu_focus.is_changed() {
+        return;
+    }
+
+    let focused_button = menu_focus.0.map(|i| menu_buttons[i]);
+    for (menu_button, mut color) in button_query.iter_mut() {
+        *color = if focused_button == Some(*menu_button) {
+            HOVERED_BUTTON.into()
+        } else {
+            NORMAL_BUTTON.into()
+        };
     }
 }
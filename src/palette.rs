@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// The parties the player can join
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Party {
+    Red,
+    Blue,
+}
+
+/// Sets of colors for the two parties
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    Standard,
+    /// Keeps the parties apart on the blue-yellow axis, which is unaffected by red-green color blindness
+    Deuteranopia,
+    /// Like the deuteranopia palette, but avoids dark reds, which look almost black with protanopia
+    Protanopia,
+}
+
+impl Palette {
+    const ALL: [Palette; 3] = [
+        Palette::Standard,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "Standard",
+            Palette::Deuteranopia => "Deuteranopia",
+            Palette::Protanopia => "Protanopia",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let idx = Palette::ALL
+            .iter()
+            .position(|palette| palette == self)
+            .unwrap_or(0);
+        Palette::ALL[(idx + 1) % Palette::ALL.len()]
+    }
+
+    /// Gets the regular and faded colors for the provided party
+    fn party_colors(&self, party: Party) -> (Color, Color) {
+        match (self, party) {
+            (Palette::Standard, Party::Red) => (RED, RED_FADED),
+            (Palette::Standard, Party::Blue) => (BLUE, BLUE_FADED),
+            (Palette::Deuteranopia, Party::Red) => {
+                (Color::rgb(0.9, 0.6, 0.0), Color::rgb(1.0, 0.85, 0.55))
+            }
+            (Palette::Deuteranopia, Party::Blue) => {
+                (Color::rgb(0.0, 0.45, 0.7), Color::rgb(0.6, 0.8, 0.95))
+            }
+            (Palette::Protanopia, Party::Red) => {
+                (Color::rgb(0.85, 0.75, 0.0), Color::rgb(1.0, 0.95, 0.6))
+            }
+            (Palette::Protanopia, Party::Blue) => {
+                (Color::rgb(0.2, 0.3, 0.8), Color::rgb(0.65, 0.75, 1.0))
+            }
+        }
+    }
+
+    /// Gets the name of the provided party's regular color, for telling the player which party is which
+    fn party_color_name(&self, party: Party) -> &'static str {
+        match (self, party) {
+            (Palette::Standard, Party::Red) => "red",
+            (Palette::Deuteranopia, Party::Red) => "orange",
+            (Palette::Protanopia, Party::Red) => "yellow",
+            (_, Party::Blue) => "blue",
+        }
+    }
+
+    /// Gets the colors for district labels that are tied and that have no voters, in that order
+    fn label_colors(&self) -> (Color, Color) {
+        match self {
+            Palette::Standard => (Color::YELLOW_GREEN, Color::GREEN),
+            Palette::Deuteranopia | Palette::Protanopia => (Color::WHITE, Color::GRAY),
+        }
+    }
+}
+
+impl Party {
+    fn opponent(&self) -> Party {
+        match self {
+            Party::Red => Party::Blue,
+            Party::Blue => Party::Red,
+        }
+    }
+}
+
+impl Colors {
+    /// Gets the colors to use when the player is in the provided party
+    pub fn new(party: Party, palette: Palette, patterns: bool) -> Self {
+        let (good_regular, good_faded) = palette.party_colors(party);
+        let (bad_regular, bad_faded) = palette.party_colors(party.opponent());
+        let (tie_label, undecided_label) = palette.label_colors();
+        Colors {
+            party,
+            palette,
+            patterns,
+            good_color_name: palette.party_color_name(party).to_string(),
+            bad_color_name: palette.party_color_name(party.opponent()).to_string(),
+            good_regular,
+            good_faded,
+            bad_regular,
+            bad_faded,
+            tie_label,
+            undecided_label,
+        }
+    }
+
+    /// Switches to the provided palette, keeping everything else the same
+    pub fn set_palette(&mut self, palette: Palette) {
+        *self = Colors::new(self.party, palette, self.patterns);
+    }
+}
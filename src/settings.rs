@@ -0,0 +1,115 @@
+use crate::*;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setting_button_system)
+            .add_system(setting_text_system);
+    }
+}
+
+/// The settings the player can change from the menus
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    Palette,
+    Patterns,
+    Music,
+    Effects,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 4] = [
+        Setting::Palette,
+        Setting::Patterns,
+        Setting::Music,
+        Setting::Effects,
+    ];
+
+    /// Describes the current value of the setting
+    fn label(&self, colors: &Colors, audio_settings: &AudioSettings) -> String {
+        match self {
+            Setting::Palette => format!("Colors: {}", colors.palette.name()),
+            Setting::Patterns => {
+                format!("Patterns: {}", if colors.patterns { "on" } else { "off" })
+            }
+            Setting::Music => format!("Music: {}", audio_settings.music.description()),
+            Setting::Effects => format!("Sound: {}", audio_settings.effects.description()),
+        }
+    }
+
+    /// Moves the setting on to its next value
+    pub fn change(&self, colors: &mut Colors, audio_settings: &mut AudioSettings) {
+        match self {
+            Setting::Palette => {
+                let palette = colors.palette.next();
+                colors.set_palette(palette);
+            }
+            Setting::Patterns => colors.patterns = !colors.patterns,
+            Setting::Music => audio_settings.music.cycle(),
+            Setting::Effects => audio_settings.effects.cycle(),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SettingButton(pub Setting);
+
+const SETTING_BUTTON: ButtonStyle = ButtonStyle {
+    size: Size {
+        width: Val::Px(280.0),
+        height: Val::Px(50.0),
+    },
+    margin: 10.0,
+    font_size: 25.0,
+};
+
+/// Spawns a button that changes the provided setting when pressed, with the provided extra components
+pub fn spawn_setting_button(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    setting: Setting,
+    colors: &Colors,
+    audio_settings: &AudioSettings,
+    components: impl Bundle,
+) {
+    spawn_button(
+        parent,
+        font,
+        &setting.label(colors, audio_settings),
+        SETTING_BUTTON,
+        SettingButton(setting),
+    )
+    .insert_bundle(components);
+}
+
+/// Handles interactions with the setting buttons.
+fn setting_button_system(
+    mut colors: ResMut<Colors>,
+    mut audio_settings: ResMut<AudioSettings>,
+    interaction_query: Query<(&Interaction, &SettingButton), Changed<Interaction>>,
+) {
+    for (interaction, setting_button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            setting_button.0.change(&mut colors, &mut audio_settings);
+        }
+    }
+}
+
+/// Handles keeping the setting buttons' text up to date
+fn setting_text_system(
+    colors: Res<Colors>,
+    audio_settings: Res<AudioSettings>,
+    button_query: Query<(&SettingButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !colors.is_changed() && !audio_settings.is_changed() {
+        return;
+    }
+
+    for (setting_button, children) in button_query.iter() {
+        if let Ok(mut text) = text_query.get_mut(children[0]) {
+            text.sections[0].value = setting_button.0.label(&colors, &audio_settings);
+        }
+    }
+}
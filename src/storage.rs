@@ -0,0 +1,91 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::*;
+
+/// What the names of the files and browser storage keys the game keeps data under start with
+const STORAGE_PREFIX: &str = "redistricting-";
+
+/// Determines whether there's any data stored under the provided name
+pub fn is_stored(name: &str) -> bool {
+    read_storage(name).is_some()
+}
+
+/// Loads the data stored under the provided name, if there is any and it can be read
+pub fn load_stored<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let stored = read_storage(name)?;
+    match ron::from_str(&stored) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            warn!("Unable to load {}: {}", name, e);
+            None
+        }
+    }
+}
+
+/// Stores the provided data under the provided name, replacing whatever was stored there before
+pub fn store<T: Serialize>(name: &str, data: &T) {
+    match ron::to_string(data) {
+        Ok(stored) => write_storage(name, &stored),
+        Err(e) => warn!("Unable to store {}: {}", name, e),
+    }
+}
+
+/// Gets the file the data with the provided name is stored in on native platforms
+#[cfg(not(target_arch = "wasm32"))]
+fn storage_file(name: &str) -> String {
+    format!("{}{}.ron", STORAGE_PREFIX, name)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_storage(name: &str) -> Option<String> {
+    std::fs::read_to_string(storage_file(name)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_storage(name: &str, stored: &str) {
+    let file = storage_file(name);
+    if let Err(e) = std::fs::write(&file, stored) {
+        warn!("Unable to store {} in {}: {}", name, file, e);
+    }
+}
+
+/// Deletes the data stored under the provided name, if there is any
+#[cfg(not(target_arch = "wasm32"))]
+pub fn delete_stored(name: &str) {
+    // there might not be anything to delete
+    let _ = std::fs::remove_file(storage_file(name));
+}
+
+/// Gets the browser storage key the data with the provided name is stored under on the web
+#[cfg(target_arch = "wasm32")]
+fn storage_key(name: &str) -> String {
+    format!("{}{}", STORAGE_PREFIX, name)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_storage(name: &str) -> Option<String> {
+    local_storage()?.get_item(&storage_key(name)).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_storage(name: &str, stored: &str) {
+    let written = local_storage().map_or(false, |storage| {
+        storage.set_item(&storage_key(name), stored).is_ok()
+    });
+    if !written {
+        warn!("Unable to store {} in browser storage", name);
+    }
+}
+
+/// Deletes the data stored under the provided name, if there is any
+#[cfg(target_arch = "wasm32")]
+pub fn delete_stored(name: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&storage_key(name));
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::window::WindowResized;
+
+use crate::*;
+
+/// The window size the UI was designed for, where everything is shown at its normal size
+const REFERENCE_WIDTH: f32 = 1280.0;
+const REFERENCE_HEIGHT: f32 = 720.0;
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 2.0;
+
+pub struct UiScalePlugin;
+
+impl Plugin for UiScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(UiScale(1.0))
+            .add_system(ui_scale_system)
+            .add_system(scale_ui_nodes_system)
+            .add_system(scale_text_system);
+    }
+}
+
+/// How much bigger or smaller than normal UI elements are shown, so the UI fits in the window in both landscape and portrait
+pub struct UiScale(pub f32);
+
+/// The pixel sizes a UI node was spawned with, before scaling
+#[derive(Component)]
+pub struct BaseStyle {
+    size: Size<Val>,
+    margin: Rect<Val>,
+    padding: Rect<Val>,
+}
+
+/// The font sizes a piece of UI text was spawned with, before scaling
+#[derive(Component)]
+pub struct BaseFontSizes(Vec<f32>);
+
+/// Handles updating the UI scale when the window is resized
+fn ui_scale_system(
+    mut resized_events: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    if resized_events.iter().last().is_none() && !ui_scale.is_added() {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary() {
+        let scale = (window.width() / REFERENCE_WIDTH)
+            .min(window.height() / REFERENCE_HEIGHT)
+            .clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        if (scale - ui_scale.0).abs() > f32::EPSILON {
+            ui_scale.0 = scale;
+        }
+    }
+}
+
+fn scale_val(val: Val, scale: f32) -> Val {
+    match val {
+        Val::Px(px) => Val::Px(px * scale),
+        val => val,
+    }
+}
+
+fn scale_rect(rect: Rect<Val>, scale: f32) -> Rect<Val> {
+    Rect {
+        left: scale_val(rect.left, scale),
+        right: scale_val(rect.right, scale),
+        top: scale_val(rect.top, scale),
+        bottom: scale_val(rect.bottom, scale),
+    }
+}
+
+fn apply_base_style(style: &mut Style, base_style: &BaseStyle, scale: f32) {
+    style.size = Size::new(
+        scale_val(base_style.size.width, scale),
+        scale_val(base_style.size.height, scale),
+    );
+    style.margin = scale_rect(base_style.margin, scale);
+    style.padding = scale_rect(base_style.padding, scale);
+}
+
+type UnscaledNodeTuple = (With<Node>, Without<BaseStyle>);
+
+/// Handles scaling the sizes and spacing of UI nodes
+fn scale_ui_nodes_system(
+    mut commands: Commands,
+    ui_scale: Res<UiScale>,
+    mut new_node_query: Query<(Entity, &mut Style), UnscaledNodeTuple>,
+    mut node_query: Query<(&BaseStyle, &mut Style)>,
+) {
+    for (entity, mut style) in new_node_query.iter_mut() {
+        let base_style = BaseStyle {
+            size: style.size,
+            margin: style.margin,
+            padding: style.padding,
+        };
+        if (ui_scale.0 - 1.0).abs() > f32::EPSILON {
+            apply_base_style(&mut style, &base_style, ui_scale.0);
+        }
+        commands.entity(entity).insert(base_style);
+    }
+
+    if ui_scale.is_changed() {
+        for (base_style, mut style) in node_query.iter_mut() {
+            apply_base_style(&mut style, base_style, ui_scale.0);
+        }
+    }
+}
+
+fn apply_base_font_sizes(text: &mut Text, base_font_sizes: &BaseFontSizes, scale: f32) {
+    for (section, base_font_size) in text.sections.iter_mut().zip(&base_font_sizes.0) {
+        section.style.font_size = base_font_size * scale;
+    }
+}
+
+type UnscaledTextTuple = (With<Node>, Without<BaseFontSizes>);
+
+/// Handles scaling the size of UI text
+fn scale_text_system(
+    mut commands: Commands,
+    ui_scale: Res<UiScale>,
+    mut new_text_query: Query<(Entity, &mut Text), UnscaledTextTuple>,
+    mut text_query: Query<(&BaseFontSizes, &mut Text)>,
+) {
+    for (entity, mut text) in new_text_query.iter_mut() {
+        let base_font_sizes = BaseFontSizes(
+            text.sections
+                .iter()
+                .map(|section| section.style.font_size)
+                .collect(),
+        );
+        if (ui_scale.0 - 1.0).abs() > f32::EPSILON {
+            apply_base_font_sizes(&mut text, &base_font_sizes, ui_scale.0);
+        }
+        commands.entity(entity).insert(base_font_sizes);
+    }
+
+    if ui_scale.is_changed() {
+        for (base_font_sizes, mut text) in text_query.iter_mut() {
+            apply_base_font_sizes(&mut text, base_font_sizes, ui_scale.0);
+        }
+    }
+}